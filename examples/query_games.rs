@@ -1,30 +1,29 @@
-use football::Football;
+use football::commands;
+use football::ranking::beebs::League;
+use football::{Football, SystemClock};
 use std::io;
 use std::io::prelude::*;
 
 #[tokio::main]
 async fn main() {
     let games = football::get_all_games().await.expect("Main error");
+    // No tables here, "!table" will not find anything
+    let tables: Vec<League> = vec![];
     let stdin = io::stdin();
     println!("Enter queries");
     for line in stdin.lock().lines() {
         let query = line.unwrap();
-        let games = games.clone();
+        if let Some(reply) = commands::respond(&query, &games, &tables, &SystemClock) {
+            for line in reply.lines(400, 5) {
+                println!("{}", line);
+            }
+            continue;
+        }
         let filteredgames = games.query(&query);
         // let filteredgames = games.mixed_query(&query);
         // for (score, country, competition, game) in filteredgames {
         //     println!("{} {} {} {}", score, country.name, competition.name, game);
         // }
-        // let filteredgames = match query.as_str() {
-        //     "today" => games.today(),
-        //     "tomorrow" => games.tomorrow(),
-        //     "yesterday" => games.yesterday(),
-        //     "ended" => games.ended(),
-        //     "live" => games.live(),
-        //     "upcoming" => games.upcoming(),
-        //     _ => games.query(&query),
-        // };
-        // let filteredgames = games.competition(&query);
         _display_football(&filteredgames);
     }
 }
@@ -0,0 +1,188 @@
+//! What happened between two snapshots of the scores.
+
+use crate::generic_structs::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    Kickoff,
+    /// One per goal side, so a snapshot that missed a 1-1 gives a goal for both teams
+    Goal {
+        team: String,
+    },
+    /// Score went down, a goal got taken back
+    ScoreCorrected,
+    FullTime,
+    Postponed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatchEvent {
+    pub game: GameId,
+    pub country: String,
+    pub competition: String,
+    pub home_team: String,
+    pub away_team: String,
+    /// Score after the event
+    pub home_score: Option<u8>,
+    pub away_score: Option<u8>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl EventKind {
+    /// Same as the "type" it serializes to
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Kickoff => "kickoff",
+            EventKind::Goal { .. } => "goal",
+            EventKind::ScoreCorrected => "score_corrected",
+            EventKind::FullTime => "full_time",
+            EventKind::Postponed => "postponed",
+            EventKind::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for MatchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let score = match (self.home_score, self.away_score) {
+            (Some(home), Some(away)) => {
+                format!("{} {}-{} {}", self.home_team, home, away, self.away_team)
+            }
+            _ => format!("{} - {}", self.home_team, self.away_team),
+        };
+        match &self.kind {
+            EventKind::Kickoff => write!(f, "Kickoff: {}", score),
+            EventKind::Goal { team } => write!(f, "Goal for {}! {}", team, score),
+            EventKind::ScoreCorrected => write!(f, "Score corrected: {}", score),
+            EventKind::FullTime => write!(f, "Full time: {}", score),
+            EventKind::Postponed => write!(f, "Postponed: {}", score),
+            EventKind::Cancelled => write!(f, "Cancelled: {}", score),
+        }
+    }
+}
+
+impl MatchEvent {
+    /// Events between an earlier and a later snapshot. Games are paired up by [GameId], games
+    /// that are only in one of the two say nothing.
+    pub fn diff(before: &Football, after: &Football) -> Vec<Self> {
+        let earlier: HashMap<_, _> = before
+            .iter_games()
+            .map(|(country, competition, game)| {
+                (GameId::new(&country.name, &competition.name, game), game)
+            })
+            .collect();
+        let mut events = vec![];
+        for (country, competition, game) in after.iter_games() {
+            let id = GameId::new(&country.name, &competition.name, game);
+            let Some(old) = earlier.get(&id) else {
+                continue;
+            };
+            let event = |kind| MatchEvent {
+                game: id.clone(),
+                country: country.name.clone(),
+                competition: competition.name.clone(),
+                home_team: game.home_team.clone(),
+                away_team: game.away_team.clone(),
+                home_score: game.home_score,
+                away_score: game.away_score,
+                kind,
+            };
+
+            let started = |g: &Game| matches!(g.status, GameStatus::Ongoing(_) | GameStatus::Ended);
+            if !started(old) && started(game) {
+                events.push(event(EventKind::Kickoff));
+            }
+            let old_home = old.home_score.unwrap_or(0);
+            let old_away = old.away_score.unwrap_or(0);
+            let home = game.home_score.unwrap_or(0);
+            let away = game.away_score.unwrap_or(0);
+            if home < old_home || away < old_away {
+                events.push(event(EventKind::ScoreCorrected));
+            } else {
+                if home > old_home {
+                    events.push(event(EventKind::Goal {
+                        team: game.home_team.clone(),
+                    }));
+                }
+                if away > old_away {
+                    events.push(event(EventKind::Goal {
+                        team: game.away_team.clone(),
+                    }));
+                }
+            }
+            if old.status != game.status {
+                match game.status {
+                    GameStatus::Ended => events.push(event(EventKind::FullTime)),
+                    GameStatus::Postponed => events.push(event(EventKind::Postponed)),
+                    GameStatus::Cancelled => events.push(event(EventKind::Cancelled)),
+                    GameStatus::Upcoming | GameStatus::Ongoing(_) => {}
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn snapshot(status: GameStatus, score: Option<(u8, u8)>) -> Football {
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![Game {
+                        home_team: String::from("Genk"),
+                        away_team: String::from("Gent"),
+                        home_score: score.map(|s| s.0),
+                        away_score: score.map(|s| s.1),
+                        start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 0, 0).unwrap(),
+                        status,
+                    }],
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn events_between_snapshots() {
+        let upcoming = snapshot(GameStatus::Upcoming, None);
+        let first_half = snapshot(GameStatus::Ongoing(String::from("20'")), Some((1, 0)));
+        let ended = snapshot(GameStatus::Ended, Some((2, 1)));
+
+        let lines = |a, b| -> Vec<String> {
+            MatchEvent::diff(a, b)
+                .iter()
+                .map(|e| e.to_string())
+                .collect()
+        };
+        assert_eq!(
+            lines(&upcoming, &first_half),
+            vec!["Kickoff: Genk 1-0 Gent", "Goal for Genk! Genk 1-0 Gent"]
+        );
+        assert_eq!(
+            lines(&first_half, &ended),
+            vec![
+                "Goal for Genk! Genk 2-1 Gent",
+                "Goal for Gent! Genk 2-1 Gent",
+                "Full time: Genk 2-1 Gent",
+            ]
+        );
+        let disallowed = snapshot(GameStatus::Ongoing(String::from("25'")), Some((0, 0)));
+        assert_eq!(
+            lines(&first_half, &disallowed),
+            vec!["Score corrected: Genk 0-0 Gent"]
+        );
+        assert!(lines(&ended, &ended).is_empty());
+        assert!(lines(&Football::default(), &ended).is_empty());
+    }
+}
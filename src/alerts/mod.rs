@@ -0,0 +1,316 @@
+//! Goal and full time alerts for whoever follows the teams or competitions involved. The
+//! `webhook` feature adds `Webhook`, which pushes them to HTTP endpoints.
+
+mod events;
+#[cfg(feature = "webhook")]
+mod webhook;
+
+pub use events::{EventKind, MatchEvent};
+#[cfg(feature = "webhook")]
+pub use webhook::{
+    DeadLetter, Webhook, WebhookConfig, WebhookError, EVENT_HEADER, SIGNATURE_HEADER,
+};
+
+use crate::search::{matches_words, query_words};
+use crate::teams::{best_match, normalize};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// What one user or channel follows
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Watchlist {
+    #[serde(default)]
+    pub teams: Vec<String>,
+    /// Matched on the words of country and competition, so "belgium first" works
+    #[serde(default)]
+    pub competitions: Vec<String>,
+}
+
+impl Watchlist {
+    pub fn is_empty(&self) -> bool {
+        self.teams.is_empty() && self.competitions.is_empty()
+    }
+
+    /// Team names have to be the same but for accents, "FC" and the like, and common short forms
+    /// (see [normalize]), so "Inter" is not "Inter Miami". Use [Subscriptions::follow_team_among]
+    /// to go from a loose name to the real one.
+    pub fn wants(&self, event: &MatchEvent) -> bool {
+        let (home, away) = (normalize(&event.home_team), normalize(&event.away_team));
+        let team = self.teams.iter().any(|followed| {
+            let followed = normalize(followed);
+            followed == home || followed == away
+        });
+        let competition = self.competitions.iter().any(|followed| {
+            matches_words(
+                &query_words(followed),
+                &[&event.country, &event.competition],
+            )
+        });
+        team || competition
+    }
+}
+
+/// Everything one subscriber gets told about a batch of events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub subscriber: String,
+    pub events: Vec<MatchEvent>,
+}
+
+/// Who follows what. A subscriber is whatever the caller uses to reach someone: a nick, a
+/// channel, ...
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscriptions {
+    /// Lowercase alias to the team name it stands for, e.g., "the blues" to "Chelsea"
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    subscribers: BTreeMap<String, Watchlist>,
+}
+
+impl Subscriptions {
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Subscriptions always serialize")
+    }
+
+    /// No subscriptions if the file does not exist yet
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Self::from_json(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(path, self.to_json())?;
+        Ok(())
+    }
+
+    pub fn add_alias(&mut self, alias: &str, team: &str) {
+        self.aliases
+            .insert(alias.trim().to_lowercase(), team.trim().to_owned());
+    }
+
+    pub fn remove_alias(&mut self, alias: &str) -> bool {
+        self.aliases.remove(&alias.trim().to_lowercase()).is_some()
+    }
+
+    /// Team name the alias stands for, or the name itself if it is not an alias
+    pub fn resolve(&self, team: &str) -> String {
+        let team = team.trim();
+        self.aliases
+            .get(&team.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| team.to_owned())
+    }
+
+    /// Follows the team, after resolving aliases. Returns the name that is followed, or None if
+    /// it was followed already.
+    pub fn follow_team(&mut self, subscriber: &str, team: &str) -> Option<String> {
+        let team = self.resolve(team);
+        let watchlist = self.subscribers.entry(subscriber.to_owned()).or_default();
+        if watchlist
+            .teams
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&team))
+        {
+            return None;
+        }
+        watchlist.teams.push(team.clone());
+        Some(team)
+    }
+
+    /// Same as [Subscriptions::follow_team], but follows the known team that best matches the
+    /// name (see [crate::teams]), e.g., all teams in the current snapshot. Falls back to the name
+    /// itself if none of them match.
+    pub fn follow_team_among(
+        &mut self,
+        subscriber: &str,
+        team: &str,
+        known_teams: &[String],
+    ) -> Option<String> {
+        let team = self.resolve(team);
+        let team = best_match(&team, known_teams).cloned().unwrap_or(team);
+        self.follow_team(subscriber, &team)
+    }
+
+    pub fn unfollow_team(&mut self, subscriber: &str, team: &str) -> bool {
+        let team = self.resolve(team);
+        self.remove_where(subscriber, |w| &mut w.teams, &team)
+    }
+
+    /// Returns false if it was followed already
+    pub fn follow_competition(&mut self, subscriber: &str, competition: &str) -> bool {
+        let competition = competition.trim();
+        let watchlist = self.subscribers.entry(subscriber.to_owned()).or_default();
+        if watchlist
+            .competitions
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(competition))
+        {
+            return false;
+        }
+        watchlist.competitions.push(competition.to_owned());
+        true
+    }
+
+    pub fn unfollow_competition(&mut self, subscriber: &str, competition: &str) -> bool {
+        self.remove_where(subscriber, |w| &mut w.competitions, competition.trim())
+    }
+
+    pub fn watchlist(&self, subscriber: &str) -> Option<&Watchlist> {
+        self.subscribers.get(subscriber)
+    }
+
+    /// Forgets everything the subscriber follows
+    pub fn remove(&mut self, subscriber: &str) -> bool {
+        self.subscribers.remove(subscriber).is_some()
+    }
+
+    /// Which subscribers get which of the events, in the order of the events. Subscribers that
+    /// want none of them are left out.
+    pub fn notifications(&self, events: &[MatchEvent]) -> Vec<Notification> {
+        self.subscribers
+            .iter()
+            .filter_map(|(subscriber, watchlist)| {
+                let events: Vec<_> = events
+                    .iter()
+                    .filter(|event| watchlist.wants(event))
+                    .cloned()
+                    .collect();
+                (!events.is_empty()).then(|| Notification {
+                    subscriber: subscriber.clone(),
+                    events,
+                })
+            })
+            .collect()
+    }
+
+    fn remove_where(
+        &mut self,
+        subscriber: &str,
+        list: impl Fn(&mut Watchlist) -> &mut Vec<String>,
+        name: &str,
+    ) -> bool {
+        let Some(watchlist) = self.subscribers.get_mut(subscriber) else {
+            return false;
+        };
+        let names = list(watchlist);
+        let before = names.len();
+        names.retain(|n| !n.eq_ignore_ascii_case(name));
+        let removed = names.len() != before;
+        if watchlist.is_empty() {
+            self.subscribers.remove(subscriber);
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic_structs::*;
+    use chrono::prelude::*;
+
+    fn event(country: &str, competition: &str, home: &str, away: &str) -> MatchEvent {
+        let game = Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: Some(1),
+            away_score: Some(0),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 0, 0).unwrap(),
+            status: GameStatus::Ongoing(String::from("12'")),
+        };
+        MatchEvent {
+            game: GameId::new(country, competition, &game),
+            country: country.to_owned(),
+            competition: competition.to_owned(),
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: game.home_score,
+            away_score: game.away_score,
+            kind: EventKind::Goal {
+                team: home.to_owned(),
+            },
+        }
+    }
+
+    #[test]
+    fn who_gets_what() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.add_alias("The Blues", "Chelsea");
+        assert_eq!(
+            subscriptions.follow_team("alice", "the blues"),
+            Some(String::from("Chelsea"))
+        );
+        assert_eq!(subscriptions.follow_team("alice", "Chelsea"), None);
+        subscriptions.follow_team("#football", "Club Brugge");
+        assert!(subscriptions.follow_competition("#football", "belgium first division"));
+        subscriptions.follow_team("bob", "Real Madrid");
+
+        let events = vec![
+            event("England", "Premier League", "Chelsea FC", "Arsenal"),
+            event("Belgium", "First Division A", "Club Brugge KV", "Genk"),
+            event("Belgium", "First Division A", "Gent", "Anderlecht"),
+            event("Belgium", "Belgian Cup", "Lierse", "RWDM"),
+        ];
+        let notifications = subscriptions.notifications(&events);
+        let got: Vec<_> = notifications
+            .iter()
+            .map(|n| {
+                let games: Vec<_> = n.events.iter().map(|e| e.home_team.as_str()).collect();
+                (n.subscriber.as_str(), games)
+            })
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("#football", vec!["Club Brugge KV", "Gent"]),
+                ("alice", vec!["Chelsea FC"]),
+            ]
+        );
+
+        // Survives a round trip, and unfollowing everything forgets the subscriber
+        let mut loaded = Subscriptions::from_json(&subscriptions.to_json()).unwrap();
+        assert_eq!(loaded, subscriptions);
+        assert!(loaded.unfollow_team("alice", "the blues"));
+        assert!(loaded.watchlist("alice").is_none());
+        assert!(!loaded.unfollow_competition("#football", "premier league"));
+    }
+
+    #[test]
+    fn same_words_are_not_enough() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.follow_team("alice", "Inter");
+        subscriptions.follow_team("bob", "Real Madrid");
+        let known = vec![
+            String::from("Club Brugge KV"),
+            String::from("Cercle Brugge"),
+        ];
+        assert_eq!(
+            subscriptions.follow_team_among("carol", "club brugge", &known),
+            Some(String::from("Club Brugge KV"))
+        );
+
+        let events = vec![
+            event("USA", "MLS", "Inter Miami", "Orlando City"),
+            event("Spain", "Primera RFEF", "Real Madrid Castilla", "Arenteiro"),
+            event(
+                "Belgium",
+                "First Division A",
+                "Cercle Brugge",
+                "Club Brugge",
+            ),
+        ];
+        let notifications = subscriptions.notifications(&events);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].subscriber, "carol");
+        assert_eq!(notifications[0].events, vec![events[2].clone()]);
+    }
+}
@@ -0,0 +1,334 @@
+//! Pushing match events to HTTP endpoints, for whatever is not a chat bot.
+
+use super::MatchEvent;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use std::io::Write;
+use std::time::Duration;
+
+/// Header with the HMAC-SHA256 of the body, as "sha256=<hex>"
+pub const SIGNATURE_HEADER: &str = "X-Football-Signature";
+/// Header with the event type, e.g., "goal"
+pub const EVENT_HEADER: &str = "X-Football-Event";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Bodies are signed when set, see [SIGNATURE_HEADER]
+    pub secret: Option<String>,
+    /// Including the first one
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubles after that
+    pub backoff: Duration,
+    pub timeout: Duration,
+    /// JSON Lines file that events go to once all attempts failed
+    pub dead_letter: Option<String>,
+}
+
+impl WebhookConfig {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            secret: None,
+            max_attempts: 4,
+            backoff: Duration::from_millis(500),
+            timeout: Duration::from_secs(10),
+            dead_letter: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WebhookError {
+    /// Endpoint answered, but not with a success
+    Status(u16),
+    /// Never got an answer: connection refused, timeout, ...
+    Request(reqwest::Error),
+}
+
+impl fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WebhookError::Status(status) => write!(f, "Endpoint answered with status {}", status),
+            WebhookError::Request(e) => write!(f, "Request failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WebhookError {}
+
+impl WebhookError {
+    /// Client errors will not go away by asking again, except for rate limiting
+    fn is_retryable(&self) -> bool {
+        match self {
+            WebhookError::Status(status) => *status == 429 || *status >= 500,
+            WebhookError::Request(_) => true,
+        }
+    }
+}
+
+/// An event that could not be delivered, one per line in the dead letter file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub url: String,
+    /// Body as it was sent
+    pub payload: String,
+    pub attempts: u32,
+    pub error: String,
+}
+
+impl DeadLetter {
+    pub fn from_jsonl(content: &str) -> Result<Vec<Self>, serde_json::Error> {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    #[serde(flatten)]
+    event: &'a MatchEvent,
+    /// Ready to show as is
+    text: String,
+}
+
+pub struct Webhook {
+    config: WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl Webhook {
+    pub fn new(config: WebhookConfig) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder().timeout(config.timeout).build()?;
+        Ok(Self { config, client })
+    }
+
+    /// JSON body for the event: its fields plus a "text" line
+    pub fn payload(event: &MatchEvent) -> String {
+        let payload = Payload {
+            event,
+            text: event.to_string(),
+        };
+        serde_json::to_string(&payload).expect("Events always serialize")
+    }
+
+    /// "sha256=<hex>" of the body, for [SIGNATURE_HEADER]
+    pub fn sign(secret: &str, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC takes keys of any length");
+        mac.update(body.as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// Posts the event, retrying with backoff. Once all attempts failed, the event goes to the
+    /// dead letter file (if any) and the last error is returned.
+    pub async fn send(&self, event: &MatchEvent) -> Result<(), WebhookError> {
+        let body = Self::payload(event);
+        let mut backoff = self.config.backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let error = match self.post(&body, event.kind.name()).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            if attempts >= self.config.max_attempts.max(1) || !error.is_retryable() {
+                log::warn!("Giving up on webhook {}: {}", self.config.url, error);
+                self.dead_letter(&body, attempts, &error);
+                return Err(error);
+            }
+            log::debug!("Webhook {} failed, retrying: {}", self.config.url, error);
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    /// Sends the events one after the other, in order. Returns the ones that failed.
+    pub async fn send_all<'a>(
+        &self,
+        events: &'a [MatchEvent],
+    ) -> Vec<(&'a MatchEvent, WebhookError)> {
+        let mut failed = vec![];
+        for event in events {
+            if let Err(e) = self.send(event).await {
+                failed.push((event, e));
+            }
+        }
+        failed
+    }
+
+    async fn post(&self, body: &str, kind: &str) -> Result<(), WebhookError> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, kind)
+            .body(body.to_owned());
+        if let Some(secret) = &self.config.secret {
+            request = request.header(SIGNATURE_HEADER, Self::sign(secret, body));
+        }
+        let response = request.send().await.map_err(WebhookError::Request)?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(WebhookError::Status(response.status().as_u16()))
+        }
+    }
+
+    fn dead_letter(&self, body: &str, attempts: u32, error: &WebhookError) {
+        let Some(path) = &self.config.dead_letter else {
+            return;
+        };
+        let letter = DeadLetter {
+            url: self.config.url.clone(),
+            payload: body.to_owned(),
+            attempts,
+            error: error.to_string(),
+        };
+        let line = serde_json::to_string(&letter).expect("Dead letters always serialize");
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = written {
+            log::error!("Could not write dead letter to {}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::EventKind;
+    use crate::generic_structs::*;
+    use chrono::prelude::*;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn event() -> MatchEvent {
+        let game = Game {
+            home_team: String::from("Genk"),
+            away_team: String::from("Gent"),
+            home_score: Some(1),
+            away_score: Some(0),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 0, 0).unwrap(),
+            status: GameStatus::Ongoing(String::from("12'")),
+        };
+        MatchEvent {
+            game: GameId::new("Belgium", "First Division A", &game),
+            country: String::from("Belgium"),
+            competition: String::from("First Division A"),
+            home_team: game.home_team,
+            away_team: game.away_team,
+            home_score: game.home_score,
+            away_score: game.away_score,
+            kind: EventKind::Goal {
+                team: String::from("Genk"),
+            },
+        }
+    }
+
+    /// Answers every request with the next status, one connection per request. Sends back the
+    /// (lowercase headers, body) of every request it got.
+    fn stand_in(statuses: Vec<u16>) -> (String, mpsc::Receiver<(Vec<String>, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut headers = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers.push(line.trim().to_lowercase());
+                }
+                let length = headers
+                    .iter()
+                    .find_map(|h| h.strip_prefix("content-length:"))
+                    .map_or(0, |l| l.trim().parse().unwrap());
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let response = format!(
+                    "HTTP/1.1 {} Whatever\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+                sender
+                    .send((headers, String::from_utf8(body).unwrap()))
+                    .unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    fn config(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            secret: Some(String::from("hunter2")),
+            backoff: Duration::from_millis(1),
+            ..WebhookConfig::new(url)
+        }
+    }
+
+    #[tokio::test]
+    async fn signed_delivery_after_a_retry() {
+        let (url, requests) = stand_in(vec![503, 200]);
+        let webhook = Webhook::new(config(&url)).unwrap();
+        webhook.send(&event()).await.unwrap();
+
+        let (_, first) = requests.recv().unwrap();
+        let (headers, body) = requests.recv().unwrap();
+        assert_eq!(first, body);
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["type"], "goal");
+        assert_eq!(payload["team"], "Genk");
+        assert_eq!(payload["text"], "Goal for Genk! Genk 1-0 Gent");
+        assert!(headers.contains(&String::from("x-football-event: goal")));
+        let signature = format!("x-football-signature: {}", Webhook::sign("hunter2", &body));
+        assert!(headers.contains(&signature));
+    }
+
+    #[tokio::test]
+    async fn dead_letter_when_giving_up() {
+        let path = std::env::temp_dir().join(format!("football-dead-{}.jsonl", std::process::id()));
+        let path = path.to_str().unwrap().to_owned();
+        let _ = std::fs::remove_file(&path);
+
+        let (url, requests) = stand_in(vec![500, 500, 404]);
+        let webhook = Webhook::new(WebhookConfig {
+            max_attempts: 2,
+            dead_letter: Some(path.clone()),
+            ..config(&url)
+        })
+        .unwrap();
+        assert!(matches!(
+            webhook.send(&event()).await,
+            Err(WebhookError::Status(500))
+        ));
+        // Not found is not worth retrying
+        assert!(matches!(
+            webhook.send(&event()).await,
+            Err(WebhookError::Status(404))
+        ));
+        assert_eq!(requests.iter().count(), 3);
+
+        let letters = DeadLetter::from_jsonl(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].attempts, 2);
+        assert_eq!(letters[1].attempts, 1);
+        assert_eq!(letters[1].error, "Endpoint answered with status 404");
+        assert_eq!(letters[0].payload, Webhook::payload(&event()));
+    }
+}
@@ -0,0 +1,110 @@
+//! What the bot says, apart from how it gets there.
+
+use crate::config::Config;
+use crate::irc::{privmsg, Message};
+use football::alerts::{EventKind, MatchEvent};
+use football::commands::{self, Tables};
+use football::{Clock, Football};
+use std::sync::{Arc, RwLock};
+
+/// Tables the bot answers from, swapped out whenever fresh ones are fetched
+pub type SharedTables = Arc<RwLock<Box<dyn Tables + Send + Sync>>>;
+
+pub struct Bot {
+    config: Config,
+    tables: SharedTables,
+    nick: String,
+}
+
+impl Bot {
+    pub fn new(config: Config, tables: SharedTables) -> Self {
+        let nick = config.server.nick.clone();
+        Self {
+            config,
+            tables,
+            nick,
+        }
+    }
+
+    /// Lines to send right after connecting
+    pub fn login(&self) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(password) = &self.config.server.password {
+            lines.push(format!("PASS {}", password));
+        }
+        lines.push(format!("NICK {}", self.nick));
+        lines.push(format!(
+            "USER {} 0 * :{}",
+            self.nick, self.config.server.realname
+        ));
+        lines
+    }
+
+    /// Answer to a line from the server, if any
+    pub fn handle(&mut self, line: &str, football: &Football, clock: &impl Clock) -> Vec<String> {
+        let Some(message) = Message::parse(line) else {
+            return vec![];
+        };
+        match message.command.as_str() {
+            "PING" => vec![format!("PONG :{}", message.params.join(" "))],
+            // Welcome, so registered
+            "001" => self
+                .config
+                .channels
+                .iter()
+                .map(|channel| format!("JOIN {}", channel.name))
+                .collect(),
+            // Nick in use
+            "433" => {
+                self.nick.push('_');
+                vec![format!("NICK {}", self.nick)]
+            }
+            "PRIVMSG" => {
+                let (Some(target), Some(text), Some(nick)) = (
+                    message.params.first(),
+                    message.params.get(1),
+                    message.nick(),
+                ) else {
+                    return vec![];
+                };
+                // Private messages get answered privately
+                let reply_to = if target.eq_ignore_ascii_case(&self.nick) {
+                    nick
+                } else {
+                    target
+                };
+                let reply = {
+                    let tables = self.tables.read().expect("Lock is never poisoned");
+                    commands::respond(text, football, &**tables, clock)
+                };
+                let Some(reply) = reply else {
+                    return vec![];
+                };
+                reply
+                    .lines(self.config.max_line_bytes, self.config.max_lines)
+                    .iter()
+                    .map(|line| privmsg(reply_to, line))
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Goals and full times for the channels that want them
+    pub fn announce(&self, events: &[MatchEvent]) -> Vec<String> {
+        let mut lines = vec![];
+        for channel in self.config.channels.iter().filter(|c| c.announce) {
+            let watchlist = channel.watchlist();
+            for event in events {
+                let wanted = matches!(
+                    event.kind,
+                    EventKind::Goal { .. } | EventKind::ScoreCorrected | EventKind::FullTime
+                );
+                if wanted && (watchlist.is_empty() || watchlist.wants(event)) {
+                    lines.push(privmsg(&channel.name, &event.to_string()));
+                }
+            }
+        }
+        lines
+    }
+}
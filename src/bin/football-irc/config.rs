@@ -0,0 +1,163 @@
+//! The bot's TOML config, e.g.,
+//!
+//! ```toml
+//! [server]
+//! host = "irc.libera.chat"
+//! port = 6667
+//! nick = "footbot"
+//!
+//! [[channels]]
+//! name = "#football"
+//! announce = true
+//! teams = ["Club Brugge", "Genk"]
+//! competitions = ["belgium first division"]
+//! ```
+
+use football::alerts::Watchlist;
+use serde::Deserialize;
+use std::error::Error;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Config {
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+    #[serde(default)]
+    pub flood: FloodConfig,
+    /// How often the scores get fetched
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,
+    /// How often the tables get fetched, they only change after a game
+    #[serde(default = "default_tables_refresh_secs")]
+    pub tables_refresh_secs: u64,
+    /// Replies longer than this get split up or cut off. IRC allows 512 bytes per line,
+    /// including the "PRIVMSG #channel :" and the prefix the server adds.
+    #[serde(default = "default_max_line_bytes")]
+    pub max_line_bytes: usize,
+    #[serde(default = "default_max_lines")]
+    pub max_lines: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ServerConfig {
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub nick: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default = "default_realname")]
+    pub realname: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChannelConfig {
+    pub name: String,
+    /// Live goal and full time announcements
+    #[serde(default)]
+    pub announce: bool,
+    /// What gets announced. Everything if both are empty.
+    #[serde(default)]
+    pub teams: Vec<String>,
+    #[serde(default)]
+    pub competitions: Vec<String>,
+}
+
+impl ChannelConfig {
+    pub fn watchlist(&self) -> Watchlist {
+        Watchlist {
+            teams: self.teams.clone(),
+            competitions: self.competitions.clone(),
+        }
+    }
+}
+
+/// Lines go out right away until `burst` lines are queued up, after that one per
+/// `interval_ms`. Most servers kick for going much faster than one line every two seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FloodConfig {
+    pub burst: u32,
+    pub interval_ms: u64,
+}
+
+impl Default for FloodConfig {
+    fn default() -> Self {
+        Self {
+            burst: 4,
+            interval_ms: 2000,
+        }
+    }
+}
+
+impl FloodConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+fn default_refresh_secs() -> u64 {
+    60
+}
+
+fn default_tables_refresh_secs() -> u64 {
+    60 * 60
+}
+
+fn default_max_line_bytes() -> usize {
+    400
+}
+
+fn default_max_lines() -> usize {
+    4
+}
+
+fn default_port() -> u16 {
+    6667
+}
+
+fn default_realname() -> String {
+    String::from("football scores")
+}
+
+impl Config {
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_toml(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let config = Config::from_toml(
+            r##"
+            [server]
+            host = "localhost"
+            nick = "footbot"
+
+            [[channels]]
+            name = "#football"
+            announce = true
+            teams = ["Genk"]
+
+            [[channels]]
+            name = "#chat"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.server.port, 6667);
+        assert_eq!(config.flood, FloodConfig::default());
+        assert_eq!(config.tables_refresh_secs, 3600);
+        assert_eq!(config.channels.len(), 2);
+        assert!(config.channels[0].announce);
+        assert!(!config.channels[1].announce);
+        assert_eq!(config.channels[0].watchlist().teams, vec!["Genk"]);
+    }
+}
@@ -0,0 +1,168 @@
+//! Just enough of the IRC protocol (RFC 1459) for a bot.
+
+use std::time::{Duration, Instant};
+
+/// Including the trailing CRLF
+const MAX_LINE_BYTES: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Who sent it, e.g., "alice!alice@example.com"
+    pub prefix: Option<String>,
+    pub command: String,
+    /// The trailing parameter (after " :") is the last one
+    pub params: Vec<String>,
+}
+
+impl Message {
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (prefix, rest) = match line.strip_prefix(':') {
+            Some(rest) => {
+                let (prefix, rest) = rest.split_once(' ')?;
+                (Some(prefix.to_owned()), rest)
+            }
+            None => (None, line),
+        };
+        let (middle, trailing) = match rest.split_once(" :") {
+            Some((middle, trailing)) => (middle, Some(trailing)),
+            None => (rest, None),
+        };
+        let mut words = middle.split(' ').filter(|word| !word.is_empty());
+        let command = words.next()?.to_uppercase();
+        let mut params: Vec<_> = words.map(String::from).collect();
+        params.extend(trailing.map(String::from));
+        Some(Self {
+            prefix,
+            command,
+            params,
+        })
+    }
+
+    /// Nick of the sender, if it came from a user
+    pub fn nick(&self) -> Option<&str> {
+        let prefix = self.prefix.as_deref()?;
+        prefix.split_once('!').map(|(nick, _)| nick)
+    }
+}
+
+/// "PRIVMSG target :text", cut off to fit in a line. Line breaks become spaces and other control
+/// characters are dropped, so text from a feed or a user cannot sneak in a command of its own.
+pub fn privmsg(target: &str, text: &str) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .filter(|c| !c.is_control())
+        .collect();
+    let start = format!("PRIVMSG {} :", target);
+    // The server puts our prefix in front when relaying, leave room for a long one
+    let room = MAX_LINE_BYTES.saturating_sub(start.len() + 2 + 100);
+    let mut end = text.len().min(room);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", start, &text[..end])
+}
+
+/// Keeps the bot from getting kicked for flooding. Every line adds `interval` to a timer, lines
+/// only have to wait once the timer runs `burst` intervals ahead of now, like most servers count.
+#[derive(Debug, Clone)]
+pub struct FloodControl {
+    burst: u32,
+    interval: Duration,
+    timer: Instant,
+}
+
+impl FloodControl {
+    pub fn new(burst: u32, interval: Duration, now: Instant) -> Self {
+        Self {
+            burst: burst.max(1),
+            interval,
+            timer: now,
+        }
+    }
+
+    /// How long to wait before sending the next line at `now`
+    pub fn delay(&mut self, now: Instant) -> Duration {
+        self.timer = self.timer.max(now);
+        let ahead = self.timer - now;
+        let wait = (ahead + self.interval).saturating_sub(self.interval * self.burst);
+        self.timer += self.interval;
+        wait
+    }
+}
+
+/// Time to wait before reconnecting, doubling after every failure up to `max`
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    min: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            next: min,
+        }
+    }
+
+    pub fn failed(&mut self) -> Duration {
+        let wait = self.next;
+        self.next = (self.next * 2).min(self.max);
+        wait
+    }
+
+    /// Back to waiting `min`, e.g., once a connection held up for a while
+    pub fn reset(&mut self) {
+        self.next = self.min;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let message =
+            Message::parse(":alice!alice@example.com PRIVMSG #football :!next genk\r\n").unwrap();
+        assert_eq!(message.nick(), Some("alice"));
+        assert_eq!(message.command, "PRIVMSG");
+        assert_eq!(message.params, vec!["#football", "!next genk"]);
+
+        let ping = Message::parse("PING :irc.example.com").unwrap();
+        assert_eq!(ping.prefix, None);
+        assert_eq!(ping.params, vec!["irc.example.com"]);
+        assert_eq!(Message::parse(":server 001 footbot").unwrap().nick(), None);
+
+        let long = "é".repeat(400);
+        assert!(privmsg("#football", &long).len() <= MAX_LINE_BYTES - 100);
+        assert_eq!(
+            privmsg("#football", "Genk\r\nQUIT :bye\x07"),
+            "PRIVMSG #football :Genk  QUIT :bye"
+        );
+    }
+
+    #[test]
+    fn backoff() {
+        let mut backoff = Backoff::new(Duration::from_secs(5), Duration::from_secs(30));
+        let waits: Vec<_> = (0..5).map(|_| backoff.failed().as_secs()).collect();
+        assert_eq!(waits, vec![5, 10, 20, 30, 30]);
+        backoff.reset();
+        assert_eq!(backoff.failed(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn flood_control() {
+        let start = Instant::now();
+        let mut flood = FloodControl::new(3, Duration::from_secs(2), start);
+        let waits: Vec<_> = (0..5).map(|_| flood.delay(start).as_secs()).collect();
+        assert_eq!(waits, vec![0, 0, 0, 2, 4]);
+        // Quiet for a while, so a full burst again
+        let later = start + Duration::from_secs(60);
+        assert_eq!(flood.delay(later), Duration::ZERO);
+    }
+}
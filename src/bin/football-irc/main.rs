@@ -0,0 +1,335 @@
+//! IRC bot answering "!score", "!table", "!next", ... and announcing goals as they happen.
+//!
+//! Usage: football-irc [config.toml], see the config module for what goes in there.
+//!
+//! Needs the irc feature: cargo run --features irc --bin football-irc
+//!
+//! Reconnects when the connection drops, waiting longer after every failed attempt.
+
+mod bot;
+mod config;
+mod irc;
+
+use bot::{Bot, SharedTables};
+use config::{Config, FloodConfig};
+use football::alerts::MatchEvent;
+use football::ranking::beebs::{Beebs, League};
+use football::{Clock, Football, SystemClock};
+use irc::{Backoff, FloodControl};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+/// A connection that lasted this long was fine, the next drop starts the backoff over
+const STABLE_CONNECTION: Duration = Duration::from_secs(5 * 60);
+
+#[tokio::main]
+async fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("football-irc.toml"));
+    let config = match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Could not read config {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    let football = Arc::new(RwLock::new(Football::default()));
+    // No tables until the first fetch
+    let tables: SharedTables = Arc::new(RwLock::new(Box::new(Vec::<League>::new())));
+    let (events_sender, events) = mpsc::channel(16);
+    let every = Duration::from_secs(config.refresh_secs);
+    let tables_every = Duration::from_secs(config.tables_refresh_secs);
+
+    let address = (config.server.host.clone(), config.server.port);
+    let flood = config.flood.clone();
+    let bot = Bot::new(config, tables.clone());
+    // Fetching is not Send, so both run on this task. Neither stops on its own.
+    tokio::select! {
+        _ = refresh(football.clone(), every, tables, tables_every, events_sender) => {}
+        _ = connect(address, bot, &flood, football, events) => {}
+    }
+}
+
+/// Keeps the bot connected, with a [Backoff] between attempts
+async fn connect(
+    address: (String, u16),
+    mut bot: Bot,
+    flood: &FloodConfig,
+    football: Arc<RwLock<Football>>,
+    mut events: mpsc::Receiver<Vec<MatchEvent>>,
+) {
+    let mut backoff = Backoff::new(Duration::from_secs(5), Duration::from_secs(5 * 60));
+    loop {
+        match TcpStream::connect(address.clone()).await {
+            Ok(stream) => {
+                // Whatever happened while disconnected is old news by now
+                while events.try_recv().is_ok() {}
+                let connected = Instant::now();
+                let result = run(
+                    stream,
+                    &mut bot,
+                    flood,
+                    football.clone(),
+                    &mut events,
+                    &SystemClock,
+                )
+                .await;
+                match result {
+                    Ok(()) => eprintln!("Server closed the connection"),
+                    Err(e) => eprintln!("Connection lost: {}", e),
+                }
+                if connected.elapsed() >= STABLE_CONNECTION {
+                    backoff.reset();
+                }
+            }
+            Err(e) => eprintln!("Could not connect to {}: {}", address.0, e),
+        }
+        let wait = backoff.failed();
+        eprintln!("Reconnecting in {}s", wait.as_secs());
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Fetches the scores every `every`, and sends on what changed since last time. The tables
+/// get fetched every `tables_every`, which had better be a multiple of `every`.
+async fn refresh(
+    football: Arc<RwLock<Football>>,
+    every: Duration,
+    tables: SharedTables,
+    tables_every: Duration,
+    events: mpsc::Sender<Vec<MatchEvent>>,
+) {
+    let mut first = true;
+    let mut tables_fetched: Option<Instant> = None;
+    loop {
+        if tables_fetched.is_none_or(|fetched| fetched.elapsed() >= tables_every) {
+            match Beebs::new().await {
+                Ok(beebs) => {
+                    *tables.write().expect("Lock is never poisoned") = Box::new(beebs);
+                    tables_fetched = Some(Instant::now());
+                }
+                // Keeps the old ones, and tries again next time
+                Err(e) => eprintln!("Fetching tables failed: {}", e),
+            }
+        }
+        match football::get_all_games().await {
+            Ok(fresh) => {
+                let changes = {
+                    let mut current = football.write().expect("Lock is never poisoned");
+                    let changes = MatchEvent::diff(&current, &fresh);
+                    *current = fresh;
+                    changes
+                };
+                // Everything is new on the first fetch, nothing to announce. Nobody takes them
+                // while reconnecting, those get dropped rather than holding up the scores.
+                if !first && !changes.is_empty() {
+                    if let Err(TrySendError::Closed(_)) = events.try_send(changes) {
+                        return;
+                    }
+                }
+                first = false;
+            }
+            Err(e) => eprintln!("Fetching scores failed: {}", e),
+        }
+        tokio::time::sleep(every).await;
+    }
+}
+
+/// Talks IRC over the stream until the server hangs up
+async fn run<S>(
+    stream: S,
+    bot: &mut Bot,
+    flood: &FloodConfig,
+    football: Arc<RwLock<Football>>,
+    events: &mut mpsc::Receiver<Vec<MatchEvent>>,
+    clock: &impl Clock,
+) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, writer) = tokio::io::split(stream);
+    let (outgoing, queue) = mpsc::unbounded_channel();
+    let flood = FloodControl::new(flood.burst, flood.interval(), Instant::now());
+    let writing = tokio::spawn(write_lines(writer, queue, flood));
+
+    let send = |lines: Vec<String>| {
+        for line in lines {
+            // Only fails once the writer stopped, which the reader notices soon enough
+            let _ = outgoing.send(line);
+        }
+    };
+    send(bot.login());
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    break;
+                };
+                let replies = {
+                    let football = football.read().expect("Lock is never poisoned");
+                    bot.handle(&line, &football, clock)
+                };
+                send(replies);
+            }
+            Some(batch) = events.recv() => send(bot.announce(&batch)),
+        }
+    }
+    drop(outgoing);
+    writing.await.map_err(std::io::Error::other)?
+}
+
+async fn write_lines<W: AsyncWrite>(
+    writer: W,
+    mut queue: mpsc::UnboundedReceiver<String>,
+    mut flood: FloodControl,
+) -> std::io::Result<()> {
+    tokio::pin!(writer);
+    while let Some(line) = queue.recv().await {
+        let wait = flood.delay(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        writer.write_all(format!("{}\r\n", line).as_bytes()).await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+    use football::alerts::EventKind;
+    use football::{Competition, Country, FixedClock, Game, GameId, GameStatus};
+    use tokio::net::TcpListener;
+
+    fn config() -> Config {
+        Config::from_toml(
+            r##"
+            [server]
+            host = "127.0.0.1"
+            nick = "footbot"
+
+            [flood]
+            burst = 2
+            interval_ms = 10
+
+            [[channels]]
+            name = "#football"
+            announce = true
+            teams = ["Genk"]
+
+            [[channels]]
+            name = "#quiet"
+            "##,
+        )
+        .unwrap()
+    }
+
+    fn football() -> Football {
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![Game {
+                        home_team: String::from("Anderlecht"),
+                        away_team: String::from("Genk"),
+                        home_score: None,
+                        away_score: None,
+                        start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 30, 0).unwrap(),
+                        status: GameStatus::Upcoming,
+                    }],
+                }],
+            }],
+        }
+    }
+
+    fn goal() -> MatchEvent {
+        let game = &football().countries[0].competitions[0].games[0];
+        MatchEvent {
+            game: GameId::new("Belgium", "First Division A", game),
+            country: String::from("Belgium"),
+            competition: String::from("First Division A"),
+            home_team: game.home_team.clone(),
+            away_team: game.away_team.clone(),
+            home_score: Some(0),
+            away_score: Some(1),
+            kind: EventKind::Goal {
+                team: String::from("Genk"),
+            },
+        }
+    }
+
+    async fn expect<R: AsyncBufReadExt + Unpin>(lines: &mut tokio::io::Lines<R>, expected: &str) {
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn against_a_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (events_sender, mut events) = mpsc::channel(1);
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            expect(&mut lines, "NICK footbot").await;
+            expect(&mut lines, "USER footbot 0 * :football scores").await;
+
+            writer
+                .write_all(b":irc.local 001 footbot :Welcome\r\n")
+                .await
+                .unwrap();
+            expect(&mut lines, "JOIN #football").await;
+            expect(&mut lines, "JOIN #quiet").await;
+            writer.write_all(b"PING :irc.local\r\n").await.unwrap();
+            expect(&mut lines, "PONG :irc.local").await;
+
+            writer
+                .write_all(b":alice!a@localhost PRIVMSG #quiet :!next genk\r\n")
+                .await
+                .unwrap();
+            expect(
+                &mut lines,
+                "PRIVMSG #quiet :Belgium First Division A: Anderlecht - Genk (in 7h 30m)",
+            )
+            .await;
+            writer
+                .write_all(b":alice!a@localhost PRIVMSG footbot :!next\r\n")
+                .await
+                .unwrap();
+            expect(&mut lines, "PRIVMSG alice :Usage: !next <team>").await;
+
+            // Only the channel that opted in
+            events_sender.send(vec![goal()]).await.unwrap();
+            expect(
+                &mut lines,
+                "PRIVMSG #football :Goal for Genk! Anderlecht 0-1 Genk",
+            )
+            .await;
+            // Hanging up ends the connection
+        });
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let config = config();
+        let flood = config.flood.clone();
+        let mut bot = Bot::new(
+            config,
+            Arc::new(RwLock::new(Box::new(Vec::<League>::new()))),
+        );
+        let football = Arc::new(RwLock::new(football()));
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 12, 0, 0).unwrap());
+        run(stream, &mut bot, &flood, football, &mut events, &clock)
+            .await
+            .unwrap();
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+//! Dashboard state and key handling, everything but the drawing.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use football::alerts::{EventKind, MatchEvent};
+use football::commands::{SCORE_HOURS_AFTER, SCORE_HOURS_BEFORE};
+use football::ranking::beebs::League;
+use football::{Clock, Football, Game, GameId};
+use ratatui::crossterm;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a game stands out after a goal
+const FLASH: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Refresh,
+    /// Table for the highlighted game, queries to try in order
+    OpenTable(Vec<String>),
+}
+
+/// One game as shown, along with what it is listed under
+#[derive(Debug, Clone)]
+pub struct Shown {
+    pub id: GameId,
+    /// Country and competition
+    pub competition: String,
+    pub game: Game,
+}
+
+#[derive(Debug)]
+pub struct App {
+    football: Football,
+    /// Like [Football::query]
+    pub filter: String,
+    pub editing_filter: bool,
+    /// Live games only, otherwise everything around now
+    pub live_only: bool,
+    /// Index in [App::shown], can be past the end when games dropped out since
+    pub selected: usize,
+    /// Table shown on top of the games
+    pub table: Option<League>,
+    /// Shown at the bottom, e.g., when fetching failed
+    pub message: Option<String>,
+    flashes: HashMap<GameId, Instant>,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            football: Football::default(),
+            filter: String::new(),
+            editing_filter: false,
+            live_only: true,
+            selected: 0,
+            table: None,
+            message: None,
+            flashes: HashMap::new(),
+        }
+    }
+
+    /// Takes a fresh snapshot. Games with a new goal flash for a while.
+    pub fn update(&mut self, fresh: Football, now: Instant) {
+        for event in MatchEvent::diff(&self.football, &fresh) {
+            if matches!(event.kind, EventKind::Goal { .. }) {
+                self.flashes.insert(event.game, now + FLASH);
+            }
+        }
+        self.flashes.retain(|_, until| *until > now);
+        self.football = fresh;
+        self.message = None;
+    }
+
+    pub fn is_flashing(&self, id: &GameId, now: Instant) -> bool {
+        self.flashes.get(id).is_some_and(|until| *until > now)
+    }
+
+    /// Games to show, grouped by competition
+    pub fn shown(&self, clock: &impl Clock) -> Vec<Shown> {
+        let games = if self.live_only {
+            self.football.live()
+        } else {
+            self.football
+                .sliding_window_with(clock, SCORE_HOURS_BEFORE, SCORE_HOURS_AFTER)
+        };
+        games
+            .query(&self.filter)
+            .iter_games()
+            .map(|(country, competition, game)| Shown {
+                id: GameId::new(&country.name, &competition.name, game),
+                competition: format!("{} {}", country.name, competition.name),
+                game: game.clone(),
+            })
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, clock: &impl Clock) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Action::Quit);
+        }
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Enter | KeyCode::Esc => self.editing_filter = false,
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(c) => self.filter.push(c),
+                _ => {}
+            }
+            self.selected = 0;
+            return None;
+        }
+        if self.table.is_some() {
+            if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                self.table = None;
+            }
+            return None;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('r') => return Some(Action::Refresh),
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Char('a') => {
+                self.live_only = !self.live_only;
+                self.selected = 0;
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.selected += 1,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let shown = self.shown(clock);
+                let game = shown.get(self.selected)?;
+                return Some(Action::OpenTable(vec![
+                    game.competition.clone(),
+                    game.game.home_team.clone(),
+                ]));
+            }
+            _ => {}
+        }
+        let count = self.shown(clock).len();
+        self.selected = self.selected.min(count.saturating_sub(1));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+    use football::{Competition, Country, FixedClock, GameStatus};
+
+    fn snapshot(genk: u8) -> Football {
+        let game = |home: &str, away: &str, score: (u8, u8)| Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: Some(score.0),
+            away_score: Some(score.1),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 30, 0).unwrap(),
+            status: GameStatus::Ongoing(String::from("30'")),
+        };
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![
+                        game("Genk", "Gent", (genk, 0)),
+                        game("Club Brugge", "Anderlecht", (0, 0)),
+                    ],
+                }],
+            }],
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn goals_flash() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 20, 0, 0).unwrap());
+        let start = Instant::now();
+        let mut app = App::new();
+        app.update(snapshot(0), start);
+        app.update(snapshot(1), start + Duration::from_secs(60));
+        let shown = app.shown(&clock);
+        assert_eq!(shown.len(), 2);
+        let later = start + Duration::from_secs(65);
+        assert!(app.is_flashing(&shown[0].id, later));
+        assert!(!app.is_flashing(&shown[1].id, later));
+        assert!(!app.is_flashing(&shown[0].id, later + FLASH));
+    }
+
+    #[test]
+    fn keys() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 20, 0, 0).unwrap());
+        let mut app = App::new();
+        app.update(snapshot(0), Instant::now());
+
+        app.handle_key(key(KeyCode::Down), &clock);
+        app.handle_key(key(KeyCode::Down), &clock);
+        assert_eq!(app.selected, 1);
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter), &clock),
+            Some(Action::OpenTable(vec![
+                String::from("Belgium First Division A"),
+                String::from("Club Brugge"),
+            ]))
+        );
+
+        // Typing a filter does not quit
+        for code in [KeyCode::Char('/'), KeyCode::Char('q'), KeyCode::Enter] {
+            assert_eq!(app.handle_key(key(code), &clock), None);
+        }
+        assert_eq!(app.filter, "q");
+        assert!(app.shown(&clock).is_empty());
+        app.handle_key(key(KeyCode::Char('/')), &clock);
+        app.handle_key(key(KeyCode::Backspace), &clock);
+        app.handle_key(key(KeyCode::Esc), &clock);
+        assert_eq!(app.shown(&clock).len(), 2);
+        assert_eq!(
+            app.handle_key(key(KeyCode::Char('q')), &clock),
+            Some(Action::Quit)
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Full screen live scores for match nights.
+//!
+//! Usage: football-tui [refresh seconds]
+//!
+//! Needs the tui feature: cargo run --features tui --bin football-tui
+
+mod app;
+mod ui;
+
+use app::{Action, App};
+use football::commands::Tables;
+use football::ranking::beebs::Beebs;
+use football::SystemClock;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+#[tokio::main]
+async fn main() {
+    let every = std::env::args()
+        .nth(1)
+        .and_then(|secs| secs.parse().ok())
+        .map_or(Duration::from_secs(30), Duration::from_secs);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, every).await;
+    ratatui::restore();
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(terminal: &mut DefaultTerminal, every: Duration) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new();
+    // Only fetched once a table is asked for
+    let mut beebs: Option<Beebs> = None;
+    let mut next_refresh = Instant::now();
+    loop {
+        if Instant::now() >= next_refresh {
+            match football::get_all_games().await {
+                Ok(football) => app.update(football, Instant::now()),
+                Err(e) => app.message = Some(format!("Fetching failed: {}", e)),
+            }
+            next_refresh = Instant::now() + every;
+        }
+        terminal.draw(|frame| ui::draw(frame, &app, &SystemClock, Instant::now()))?;
+
+        // Wakes up now and then even without keys, for refreshing and ending flashes
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match app.handle_key(key, &SystemClock) {
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::Refresh) => next_refresh = Instant::now(),
+            Some(Action::OpenTable(queries)) => {
+                if beebs.is_none() {
+                    match Beebs::new().await {
+                        Ok(fetched) => beebs = Some(fetched),
+                        Err(e) => {
+                            app.message = Some(format!("Fetching tables failed: {}", e));
+                            continue;
+                        }
+                    }
+                }
+                let tables = beebs.as_ref().expect("Fetched just now");
+                match queries.iter().find_map(|query| tables.table(query)) {
+                    Some(league) => app.table = Some(league.clone()),
+                    None => app.message = Some(format!("No table found for {}", queries[0])),
+                }
+            }
+            None => {}
+        }
+    }
+}
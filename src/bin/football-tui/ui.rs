@@ -0,0 +1,171 @@
+//! Drawing the dashboard.
+
+use crate::app::App;
+use football::Clock;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::time::Instant;
+
+pub fn draw(frame: &mut Frame, app: &App, clock: &impl Clock, now: Instant) {
+    let [games, bottom] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    draw_games(frame, games, app, clock, now);
+    frame.render_widget(Paragraph::new(status_line(app)), bottom);
+    if app.table.is_some() {
+        draw_table(frame, centered(games), app);
+    }
+}
+
+fn draw_games(frame: &mut Frame, area: Rect, app: &App, clock: &impl Clock, now: Instant) {
+    let shown = app.shown(clock);
+    let mut items = vec![];
+    let mut selected = None;
+    let mut competition = None;
+    for (idx, game) in shown.iter().enumerate() {
+        if competition != Some(&game.competition) {
+            let header = Span::styled(
+                game.competition.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            );
+            items.push(ListItem::new(Line::from(header)));
+            competition = Some(&game.competition);
+        }
+        if idx == app.selected.min(shown.len() - 1) {
+            selected = Some(items.len());
+        }
+        let style = if app.is_flashing(&game.id, now) {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        items.push(ListItem::new(format!("  {}", game.game)).style(style));
+    }
+    let title = if app.live_only {
+        " Live games (a: all games) "
+    } else {
+        " Games around now (a: live only) "
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(selected);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_table(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(league) = &app.table else {
+        return;
+    };
+    let lines: Vec<_> = league
+        .entries
+        .iter()
+        .map(|entry| Line::from(entry.to_string()))
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} (esc: close) ", league.name()));
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn status_line(app: &App) -> String {
+    if app.editing_filter {
+        return format!("Filter: {}_", app.filter);
+    }
+    let mut line = String::from("q: quit  /: filter  r: refresh  enter: table");
+    if !app.filter.is_empty() {
+        line.push_str(&format!("  [filter: {}]", app.filter));
+    }
+    if let Some(message) = &app.message {
+        line.push_str(&format!("  {}", message));
+    }
+    line
+}
+
+/// Middle part of the area, for popups
+fn centered(area: Rect) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ])
+    .areas(area);
+    let [_, middle, _] = Layout::horizontal([
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+    ])
+    .areas(middle);
+    middle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+    use football::{Competition, Country, FixedClock, Football, Game, GameStatus};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn grouped_by_competition() {
+        let game = |home: &str, away: &str| Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: Some(1),
+            away_score: Some(0),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 30, 0).unwrap(),
+            status: GameStatus::Ongoing(String::from("30'")),
+        };
+        let competition = |name: &str, games| Competition {
+            name: name.to_owned(),
+            games,
+        };
+        let mut app = App::new();
+        app.update(
+            Football {
+                countries: vec![Country {
+                    name: String::from("Belgium"),
+                    competitions: vec![
+                        competition(
+                            "First Division A",
+                            vec![game("Genk", "Gent"), game("Club Brugge", "Anderlecht")],
+                        ),
+                        competition("Cup", vec![game("Lierse", "RWDM")]),
+                    ],
+                }],
+            },
+            Instant::now(),
+        );
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 20, 0, 0).unwrap());
+
+        let mut terminal = Terminal::new(TestBackend::new(50, 8)).unwrap();
+        terminal
+            .draw(|frame| draw(frame, &app, &clock, Instant::now()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_owned()
+            })
+            .collect();
+        assert!(rows[0].contains("Live games"));
+        assert_eq!(
+            rows[1],
+            "│Belgium First Division A                        │"
+        );
+        assert!(rows[2].starts_with("│  (30') Genk 1-0 Gent"));
+        assert!(rows[4].starts_with("│Belgium Cup"));
+        assert!(rows[7].starts_with("q: quit"));
+    }
+}
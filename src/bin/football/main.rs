@@ -0,0 +1,293 @@
+//! Command line client: scores, tables, and exports without writing any code.
+//!
+//! Needs the cli feature: cargo install --path . --features cli, or
+//! cargo run --features cli --bin football -- games belgium
+
+mod output;
+
+use chrono::{Duration, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
+use football::commands::{Command, Reply};
+use football::ranking::beebs::{Beebs, League};
+use football::{Clock, Football, SystemClock};
+use output::Zone;
+use std::error::Error;
+
+#[derive(Debug, Parser)]
+#[command(name = "football", about = "Football scores and tables")]
+struct Cli {
+    /// JSON instead of plain text
+    #[arg(long, global = true)]
+    json: bool,
+    /// Time zone for showing times and picking days: utc, local, or an offset like +02:00
+    #[arg(long, global = true, default_value = "local")]
+    tz: Zone,
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    /// Games, filtered by words matching country, competition, or teams
+    Games {
+        query: Vec<String>,
+        /// today, tomorrow, yesterday, or a date like 2024-03-02
+        #[arg(long)]
+        date: Option<Day>,
+        #[arg(long, value_enum)]
+        status: Option<Status>,
+    },
+    /// League table, found through the BBC's search
+    Table { query: Vec<String> },
+    /// Last and next game of a team
+    Team { name: Vec<String> },
+    /// Games ranked by how well they match, best first
+    Search { query: Vec<String> },
+    /// All games matching the query, as CSV, JSON Lines, or iCalendar
+    Export {
+        query: Vec<String>,
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+        /// Written to stdout if not given
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Status {
+    Live,
+    Ended,
+    Upcoming,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Jsonl,
+    Ical,
+}
+
+/// A day relative to today, or a fixed one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Day {
+    Relative(i64),
+    Date(NaiveDate),
+}
+
+impl std::str::FromStr for Day {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "today" => Ok(Day::Relative(0)),
+            "tomorrow" => Ok(Day::Relative(1)),
+            "yesterday" => Ok(Day::Relative(-1)),
+            date => date
+                .parse()
+                .map(Day::Date)
+                .map_err(|_| format!("Not a day: {} (try today or 2024-03-02)", s)),
+        }
+    }
+}
+
+impl Day {
+    fn date(&self, clock: &impl Clock, zone: &Zone) -> NaiveDate {
+        match self {
+            Day::Relative(days) => zone.date(clock.now() + Duration::days(*days)),
+            Day::Date(date) => *date,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli, &SystemClock).await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli, clock: &impl Clock) -> Result<(), Box<dyn Error>> {
+    let zone = cli.tz;
+    match cli.command {
+        Subcommands::Games {
+            query,
+            date,
+            status,
+        } => {
+            let football = football::get_all_games().await?;
+            let games = select_games(&football, &query.join(" "), date, status, clock, &zone);
+            if cli.json {
+                println!("{}", output::games_json(&games));
+            } else {
+                print!("{}", output::games_plain(&games, &zone));
+            }
+        }
+        Subcommands::Table { query } => {
+            let query = query.join(" ");
+            let beebs = Beebs::new().await?;
+            let league = beebs
+                .get_league(&query)
+                .ok_or_else(|| format!("No table found for {}", query))?;
+            if cli.json {
+                println!("{}", output::table_json(league));
+            } else {
+                print!("{}", output::table_plain(league));
+            }
+        }
+        Subcommands::Team { name } => {
+            let name = name.join(" ");
+            let football = football::get_all_games().await?;
+            let tables: Vec<League> = vec![];
+            let replies: Vec<_> = [Command::Last(name.clone()), Command::Next(name)]
+                .iter()
+                .map(|command| command.run(&football, &tables, clock))
+                .collect();
+            if cli.json {
+                let [last, next] = [&replies[0], &replies[1]].map(|reply| match reply {
+                    Reply::Game { game, .. } => Some(game),
+                    _ => None,
+                });
+                println!("{}", output::team_json(&football, last, next));
+            } else {
+                for (label, reply) in ["Last", "Next"].iter().zip(&replies) {
+                    let line = match reply {
+                        Reply::Game {
+                            competition, game, ..
+                        } => format!("{}: {}", competition, output::game_plain(game, &zone)),
+                        other => other.lines(usize::MAX, 1).concat(),
+                    };
+                    println!("{}: {}", label, line);
+                }
+            }
+        }
+        Subcommands::Search { query } => {
+            let football = football::get_all_games().await?;
+            let results = football.mixed_query(&query.join(" "));
+            if cli.json {
+                println!("{}", output::search_json(&results));
+            } else {
+                print!("{}", output::search_plain(&results, &zone));
+            }
+        }
+        Subcommands::Export {
+            query,
+            format,
+            output,
+        } => {
+            let football = football::get_all_games().await?.query(&query.join(" "));
+            let content = match format {
+                ExportFormat::Csv => football.to_csv(),
+                ExportFormat::Jsonl => football.to_jsonl(),
+                ExportFormat::Ical => football.to_ical_with(clock, "Football"),
+            };
+            match output {
+                Some(path) => std::fs::write(path, content)?,
+                None => print!("{}", content),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn select_games(
+    football: &Football,
+    query: &str,
+    date: Option<Day>,
+    status: Option<Status>,
+    clock: &impl Clock,
+    zone: &Zone,
+) -> Football {
+    let mut games = football.query(query);
+    if let Some(day) = date {
+        let day = day.date(clock, zone);
+        games = games.generic_filter(|game| zone.date(game.start_time) == day);
+    }
+    match status {
+        Some(Status::Live) => games.live(),
+        Some(Status::Ended) => games.ended(),
+        Some(Status::Upcoming) => games.upcoming(),
+        None => games,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+    use football::{Competition, Country, FixedClock, Game, GameStatus};
+
+    #[test]
+    fn arguments() {
+        let cli = Cli::try_parse_from([
+            "football", "games", "belgium", "--date", "tomorrow", "--status", "upcoming", "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command,
+            Subcommands::Games {
+                date: Some(Day::Relative(1)),
+                status: Some(Status::Upcoming),
+                ..
+            }
+        ));
+        assert!(Cli::try_parse_from(["football", "games", "--date", "someday"]).is_err());
+        assert!(Cli::try_parse_from(["football", "export", "--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn selecting_games() {
+        let game = |home: &str, hour, status| Game {
+            home_team: home.to_owned(),
+            away_team: String::from("Gent"),
+            home_score: None,
+            away_score: None,
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, hour, 30, 0).unwrap(),
+            status,
+        };
+        let football = Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![
+                        game("Genk", 13, GameStatus::Ended),
+                        game("Mechelen", 23, GameStatus::Upcoming),
+                    ],
+                }],
+            }],
+        };
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 18, 0, 0).unwrap());
+        let teams = |games: Football| -> Vec<String> {
+            games
+                .iter_games()
+                .map(|(_, _, game)| game.home_team.clone())
+                .collect()
+        };
+        // 23:30 UTC is already tomorrow two hours east
+        let east: Zone = "+02:00".parse().unwrap();
+        let today = Some(Day::Relative(0));
+        assert_eq!(
+            teams(select_games(&football, "", today, None, &clock, &east)),
+            vec!["Genk"]
+        );
+        assert_eq!(
+            teams(select_games(&football, "", today, None, &clock, &Zone::Utc)),
+            vec!["Genk", "Mechelen"]
+        );
+        assert_eq!(
+            teams(select_games(
+                &football,
+                "gent",
+                None,
+                Some(Status::Upcoming),
+                &clock,
+                &Zone::Utc
+            )),
+            vec!["Mechelen"]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Printing results, either for people (plain) or for other programs (JSON).
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+use football::ranking::beebs::League;
+use football::{Competition, Country, Football, Game, GameRow, GameStatus};
+use serde::Serialize;
+
+/// Time zone times are shown in, and days are counted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl std::str::FromStr for Zone {
+    type Err = String;
+
+    /// "utc", "local", or an offset like "+02:00"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "utc" | "z" => Ok(Zone::Utc),
+            "local" => Ok(Zone::Local),
+            offset => offset
+                .parse::<FixedOffset>()
+                .map(Zone::Fixed)
+                .map_err(|_| format!("Not a time zone: {} (try utc, local, or +02:00)", s)),
+        }
+    }
+}
+
+impl Zone {
+    pub fn format(&self, time: DateTime<Utc>, format: &str) -> String {
+        match self {
+            Zone::Utc => time.format(format).to_string(),
+            Zone::Local => time.with_timezone(&Local).format(format).to_string(),
+            Zone::Fixed(offset) => time.with_timezone(offset).format(format).to_string(),
+        }
+    }
+
+    pub fn date(&self, time: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Zone::Utc => time.date_naive(),
+            Zone::Local => time.with_timezone(&Local).date_naive(),
+            Zone::Fixed(offset) => time.with_timezone(offset).date_naive(),
+        }
+    }
+}
+
+/// Games grouped by competition, e.g.,
+///
+/// ```text
+/// Belgium First Division A
+///   Sat 02 Mar 20:30  Genk - Gent
+///   FT                Club Brugge 2-1 Anderlecht
+/// ```
+pub fn games_plain(football: &Football, zone: &Zone) -> String {
+    let mut result = String::new();
+    for country in &football.countries {
+        for competition in &country.competitions {
+            result.push_str(&format!("{} {}\n", country.name, competition.name));
+            for game in &competition.games {
+                result.push_str(&format!("  {}\n", game_plain(game, zone)));
+            }
+        }
+    }
+    if result.is_empty() {
+        result.push_str("No games found\n");
+    }
+    result
+}
+
+/// Status or kickoff, then the teams
+pub fn game_plain(game: &Game, zone: &Zone) -> String {
+    let score = |game: &Game| {
+        format!(
+            "{} {}-{} {}",
+            game.home_team,
+            game.home_score.unwrap_or(0),
+            game.away_score.unwrap_or(0),
+            game.away_team
+        )
+    };
+    let (when, what) = match &game.status {
+        GameStatus::Upcoming => (
+            zone.format(game.start_time, "%a %d %b %H:%M"),
+            format!("{} - {}", game.home_team, game.away_team),
+        ),
+        GameStatus::Ongoing(time) => (time.clone(), score(game)),
+        GameStatus::Ended => (String::from("FT"), score(game)),
+        GameStatus::Postponed => (
+            String::from("postponed"),
+            format!("{} - {}", game.home_team, game.away_team),
+        ),
+        GameStatus::Cancelled => (
+            String::from("cancelled"),
+            format!("{} - {}", game.home_team, game.away_team),
+        ),
+    };
+    format!("{:<16}  {}", when, what)
+}
+
+/// Array of [GameRow]s
+pub fn games_json(football: &Football) -> String {
+    to_json(&football.to_rows())
+}
+
+pub fn table_plain(league: &League) -> String {
+    let mut result = format!("{}\n", league.name());
+    for entry in &league.entries {
+        result.push_str(&format!("  {}\n", entry));
+    }
+    result
+}
+
+/// Array of [football::EntryRow]s
+pub fn table_json(league: &League) -> String {
+    to_json(&league.to_rows())
+}
+
+/// Best match first, lower scores are better
+pub fn search_plain(results: &[(f64, Country, Competition, Game)], zone: &Zone) -> String {
+    if results.is_empty() {
+        return String::from("No games found\n");
+    }
+    results
+        .iter()
+        .map(|(score, country, competition, game)| {
+            format!(
+                "{:.2}  {} {}: {}\n",
+                score,
+                country.name,
+                competition.name,
+                game_plain(game, zone)
+            )
+        })
+        .collect()
+}
+
+pub fn search_json(results: &[(f64, Country, Competition, Game)]) -> String {
+    #[derive(Serialize)]
+    struct Hit {
+        score: f64,
+        #[serde(flatten)]
+        game: GameRow,
+    }
+    let hits: Vec<_> = results
+        .iter()
+        .map(|(score, country, competition, game)| Hit {
+            score: *score,
+            game: GameRow::new(country, competition, game),
+        })
+        .collect();
+    to_json(&hits)
+}
+
+/// Last and next game of a team as [GameRow]s, null when there is none
+pub fn team_json(football: &Football, last: Option<&Game>, next: Option<&Game>) -> String {
+    #[derive(Serialize)]
+    struct Team {
+        last: Option<GameRow>,
+        next: Option<GameRow>,
+    }
+    let row = |wanted: &Game| {
+        football
+            .iter_games()
+            .find(|(_, _, game)| {
+                game.home_team == wanted.home_team
+                    && game.away_team == wanted.away_team
+                    && game.start_time == wanted.start_time
+            })
+            .map(|(country, competition, game)| GameRow::new(country, competition, game))
+    };
+    to_json(&Team {
+        last: last.and_then(row),
+        next: next.and_then(row),
+    })
+}
+
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("Output always serializes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn football() -> Football {
+        let game = |home: &str, away: &str, score: Option<(u8, u8)>, status| Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: score.map(|s| s.0),
+            away_score: score.map(|s| s.1),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 30, 0).unwrap(),
+            status,
+        };
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![
+                        game("Genk", "Gent", None, GameStatus::Upcoming),
+                        game(
+                            "Club Brugge",
+                            "Anderlecht",
+                            Some((2, 1)),
+                            GameStatus::Ongoing(String::from("67'")),
+                        ),
+                    ],
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn plain_and_json() {
+        let zone: Zone = "+01:00".parse().unwrap();
+        assert_eq!(
+            games_plain(&football(), &zone),
+            "Belgium First Division A\n\
+             \x20 Sat 02 Mar 20:30  Genk - Gent\n\
+             \x20 67'               Club Brugge 2-1 Anderlecht\n"
+        );
+        assert_eq!(games_plain(&Football::default(), &zone), "No games found\n");
+
+        let rows: Vec<GameRow> = serde_json::from_str(&games_json(&football())).unwrap();
+        assert_eq!(rows, football().to_rows());
+        assert!("mars".parse::<Zone>().is_err());
+        assert_eq!("UTC".parse::<Zone>(), Ok(Zone::Utc));
+    }
+
+    #[test]
+    fn team_rows() {
+        let football = football();
+        let genk = &football.countries[0].competitions[0].games[0];
+        let team: serde_json::Value =
+            serde_json::from_str(&team_json(&football, None, Some(genk))).unwrap();
+        assert!(team["last"].is_null());
+        let next: GameRow = serde_json::from_value(team["next"].clone()).unwrap();
+        assert_eq!(next, football.to_rows()[0]);
+    }
+}
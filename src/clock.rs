@@ -0,0 +1,157 @@
+//! Anything that depends on "now" goes through a Clock, so tests can pick their own now.
+
+use crate::generic_structs::*;
+use chrono::prelude::*;
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The actual current time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Always the same point in time
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl Game {
+    /// Kickoff relative to now: "in 2h 15m" or "started 30' ago". Once kickoff is further away
+    /// than `threshold` (either way), gives the kickoff time in `tz` instead, e.g., "Sat 20:45".
+    pub fn relative_start<Tz: TimeZone>(
+        &self,
+        clock: &impl Clock,
+        threshold: chrono::Duration,
+        tz: &Tz,
+    ) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let now = clock.now();
+        let diff = self.start_time - now;
+        if diff > threshold || -diff > threshold {
+            let local_start = self.start_time.with_timezone(tz);
+            let local_now = now.with_timezone(tz);
+            let days_away = (local_start.date_naive() - local_now.date_naive()).num_days();
+            let format = if days_away == 0 {
+                "%H:%M"
+            } else if days_away.abs() < 7 {
+                "%a %H:%M"
+            } else {
+                "%-d %b %H:%M"
+            };
+            local_start.format(format).to_string()
+        } else if diff >= chrono::Duration::zero() {
+            format!("in {}", humanize(diff))
+        } else if -diff < chrono::Duration::hours(1) {
+            format!("started {}' ago", (-diff).num_minutes())
+        } else {
+            format!("started {} ago", humanize(-diff))
+        }
+    }
+
+    /// Same as Display, but upcoming games get their kickoff relative to now (see
+    /// [Game::relative_start]) instead of a full UTC timestamp.
+    pub fn display_relative<Tz: TimeZone>(
+        &self,
+        clock: &impl Clock,
+        threshold: chrono::Duration,
+        tz: &Tz,
+    ) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        match self.status {
+            GameStatus::Upcoming => format!(
+                "({}) {} - {}",
+                self.relative_start(clock, threshold, tz),
+                self.home_team,
+                self.away_team
+            ),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// "3d 2h", "2h 15m", "45m". Only the two biggest units, rounded down.
+fn humanize(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes();
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_at(start_time: DateTime<Utc>) -> Game {
+        Game {
+            home_team: String::from("Club Brugge"),
+            away_team: String::from("Anderlecht"),
+            home_score: None,
+            away_score: None,
+            start_time,
+            status: GameStatus::Upcoming,
+        }
+    }
+
+    #[test]
+    fn relative_kickoff() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 18, 30, 0).unwrap());
+        let threshold = chrono::Duration::hours(12);
+        let upcoming = game_at(Utc.with_ymd_and_hms(2024, 3, 2, 20, 45, 0).unwrap());
+        assert_eq!(
+            upcoming.relative_start(&clock, threshold, &Utc),
+            "in 2h 15m"
+        );
+        let started = game_at(Utc.with_ymd_and_hms(2024, 3, 2, 18, 0, 0).unwrap());
+        assert_eq!(
+            started.relative_start(&clock, threshold, &Utc),
+            "started 30' ago"
+        );
+        let long_ago = game_at(Utc.with_ymd_and_hms(2024, 3, 2, 15, 0, 0).unwrap());
+        assert_eq!(
+            long_ago.relative_start(&clock, threshold, &Utc),
+            "started 3h 30m ago"
+        );
+    }
+
+    #[test]
+    fn absolute_past_threshold() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 18, 30, 0).unwrap());
+        let threshold = chrono::Duration::hours(12);
+        let brussels = FixedOffset::east_opt(3600).unwrap();
+        let sunday = game_at(Utc.with_ymd_and_hms(2024, 3, 3, 19, 45, 0).unwrap());
+        assert_eq!(
+            sunday.relative_start(&clock, threshold, &brussels),
+            "Sun 20:45"
+        );
+        let later = game_at(Utc.with_ymd_and_hms(2024, 3, 30, 19, 45, 0).unwrap());
+        assert_eq!(
+            later.relative_start(&clock, threshold, &brussels),
+            "30 Mar 20:45"
+        );
+        assert_eq!(
+            sunday.display_relative(&clock, threshold, &brussels),
+            "(Sun 20:45) Club Brugge - Anderlecht"
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! Chat commands like "!score genk" or "!table belgium", parsed and answered without caring
+//! about where they came from. Frontends only read lines and send replies.
+
+use crate::clock::Clock;
+use crate::generic_structs::*;
+use crate::ranking::beebs::{Beebs, Entry, League};
+use crate::search::{matches_words, query_words};
+use crate::teams::{best_match, similarity};
+use std::fmt;
+
+/// Lines starting with this are commands
+pub const PREFIX: char = '!';
+
+/// Hours before and after now that "!score" takes games from, see [Football::sliding_window]
+pub const SCORE_HOURS_BEFORE: u8 = 10;
+pub const SCORE_HOURS_AFTER: u8 = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Games around now, optionally filtered like [Football::query]
+    Score(String),
+    Live(String),
+    Today(String),
+    Tomorrow(String),
+    Yesterday(String),
+    /// Next game of a team
+    Next(String),
+    /// Last finished game of a team
+    Last(String),
+    /// League table, by league or by team
+    Table(String),
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCommandError {
+    /// Just chat, ignore it
+    NotACommand,
+    Unknown(String),
+    MissingArgument {
+        command: String,
+        usage: &'static str,
+    },
+}
+
+impl fmt::Display for ParseCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCommandError::NotACommand => write!(f, "Not a command"),
+            ParseCommandError::Unknown(command) => {
+                write!(
+                    f,
+                    "Unknown command {}{}, try {}help",
+                    PREFIX, command, PREFIX
+                )
+            }
+            ParseCommandError::MissingArgument { command, usage } => {
+                write!(f, "Usage: {}{} {}", PREFIX, command, usage)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseCommandError {}
+
+impl Command {
+    /// "!next genk" and "!NEXT  Genk " are the same command
+    pub fn parse(line: &str) -> Result<Self, ParseCommandError> {
+        let line = line.trim();
+        let Some(rest) = line.strip_prefix(PREFIX) else {
+            return Err(ParseCommandError::NotACommand);
+        };
+        let (name, argument) = match rest.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim().to_owned()),
+            None => (rest, String::new()),
+        };
+        let name = name.to_lowercase();
+        let required = |usage| {
+            if argument.is_empty() {
+                Err(ParseCommandError::MissingArgument {
+                    command: name.clone(),
+                    usage,
+                })
+            } else {
+                Ok(argument.clone())
+            }
+        };
+        match name.as_str() {
+            "score" | "scores" => Ok(Command::Score(argument)),
+            "live" => Ok(Command::Live(argument)),
+            "today" => Ok(Command::Today(argument)),
+            "tomorrow" => Ok(Command::Tomorrow(argument)),
+            "yesterday" => Ok(Command::Yesterday(argument)),
+            "next" => required("<team>").map(Command::Next),
+            "last" => required("<team>").map(Command::Last),
+            "table" => required("<league or team>").map(Command::Table),
+            "help" => Ok(Command::Help),
+            "" => Err(ParseCommandError::NotACommand),
+            _ => Err(ParseCommandError::Unknown(name)),
+        }
+    }
+
+    /// Answers the command. Nothing is fetched here, the caller keeps football and tables fresh.
+    pub fn run(&self, football: &Football, tables: &dyn Tables, clock: &impl Clock) -> Reply {
+        match self {
+            Command::Score(query) => games(
+                football.sliding_window_with(clock, SCORE_HOURS_BEFORE, SCORE_HOURS_AFTER),
+                query,
+            ),
+            Command::Live(query) => games(football.live(), query),
+            Command::Today(query) => games(football.today_with(clock), query),
+            Command::Tomorrow(query) => games(football.tomorrow_with(clock), query),
+            Command::Yesterday(query) => games(football.yesterday_with(clock), query),
+            Command::Next(team) => {
+                let now = clock.now();
+                let next = team_games(football, team)
+                    .filter(|(_, _, game)| {
+                        game.status == GameStatus::Upcoming && game.start_time >= now
+                    })
+                    .min_by_key(|(_, _, game)| game.start_time);
+                match next {
+                    Some((country, competition, game)) => Reply::Game {
+                        competition: format!("{} {}", country.name, competition.name),
+                        game: game.clone(),
+                        when: game.relative_start(clock, chrono::Duration::hours(24), &chrono::Utc),
+                    },
+                    None => Reply::Text(format!("No upcoming game found for {}", team)),
+                }
+            }
+            Command::Last(team) => {
+                let last = team_games(football, team)
+                    .filter(|(_, _, game)| game.status == GameStatus::Ended)
+                    .max_by_key(|(_, _, game)| game.start_time);
+                match last {
+                    Some((country, competition, game)) => Reply::Game {
+                        competition: format!("{} {}", country.name, competition.name),
+                        game: game.clone(),
+                        when: game.start_time.format("%a %-d %b").to_string(),
+                    },
+                    None => Reply::Text(format!("No finished game found for {}", team)),
+                }
+            }
+            Command::Table(query) => match tables.table(query) {
+                Some(league) => {
+                    let teams: Vec<_> = league.entries.iter().map(|e| e.team.clone()).collect();
+                    let highlight = best_match(query, &teams)
+                        .and_then(|team| teams.iter().position(|t| t == team));
+                    Reply::Table {
+                        league: league.name().to_owned(),
+                        entries: league.entries.clone(),
+                        highlight,
+                    }
+                }
+                None => Reply::Text(format!("No table found for {}", query)),
+            },
+            Command::Help => Reply::Text(format!(
+                "Commands: {p}score, {p}live, {p}today, {p}tomorrow, {p}yesterday [query], \
+                 {p}next <team>, {p}last <team>, {p}table <league or team>",
+                p = PREFIX
+            )),
+        }
+    }
+}
+
+/// Parses and runs the line. None if it is not a command, so nothing should be said.
+pub fn respond(
+    line: &str,
+    football: &Football,
+    tables: &dyn Tables,
+    clock: &impl Clock,
+) -> Option<Reply> {
+    match Command::parse(line) {
+        Ok(command) => Some(command.run(football, tables, clock)),
+        Err(ParseCommandError::NotACommand) => None,
+        Err(e) => Some(Reply::Text(e.to_string())),
+    }
+}
+
+/// What a command answers with. Chat frontends can use [Reply::lines], others can lay it out
+/// however they like.
+#[derive(Debug, Clone)]
+pub enum Reply {
+    Games(Football),
+    Game {
+        /// Country and competition
+        competition: String,
+        game: Game,
+        /// Kickoff, ready to show
+        when: String,
+    },
+    Table {
+        league: String,
+        entries: Vec<Entry>,
+        /// Index in entries of the team that was asked for
+        highlight: Option<usize>,
+    },
+    Text(String),
+}
+
+impl Reply {
+    /// At most `max_lines` lines of at most `max_bytes` bytes
+    pub fn lines(&self, max_bytes: usize, max_lines: usize) -> Vec<String> {
+        match self {
+            Reply::Games(football) if football.countries.is_empty() => {
+                vec![String::from("No games found")]
+            }
+            Reply::Games(football) => football.render_lines(max_bytes, max_lines),
+            Reply::Game {
+                competition,
+                game,
+                when,
+            } if game.status == GameStatus::Upcoming => vec![format!(
+                "{}: {} - {} ({})",
+                competition, game.home_team, game.away_team, when
+            )],
+            Reply::Game {
+                competition,
+                game,
+                when,
+            } => vec![format!("{}: {} ({})", competition, game, when)],
+            Reply::Table {
+                league,
+                entries,
+                highlight,
+            } => {
+                // Only the teams around the one asked for, otherwise all that fits
+                let entries = match highlight {
+                    Some(idx) => {
+                        let league = League {
+                            name: league.clone(),
+                            entries: entries.clone(),
+                        };
+                        league.get_ranking_around(*idx).to_vec()
+                    }
+                    None => entries.clone(),
+                };
+                let mut lines = vec![];
+                let mut line = format!("{}:", league);
+                for entry in entries {
+                    let entry = entry.to_string();
+                    if line.len() + entry.len() + 1 > max_bytes && !line.ends_with(':') {
+                        lines.push(std::mem::take(&mut line));
+                        if lines.len() == max_lines {
+                            return lines;
+                        }
+                        line = entry;
+                    } else {
+                        line.push(' ');
+                        line.push_str(&entry);
+                    }
+                }
+                lines.push(line);
+                lines.truncate(max_lines);
+                lines
+            }
+            Reply::Text(text) => vec![text.clone()],
+        }
+    }
+}
+
+/// Where league tables come from
+pub trait Tables {
+    fn table(&self, query: &str) -> Option<&League>;
+}
+
+impl Tables for Beebs {
+    fn table(&self, query: &str) -> Option<&League> {
+        self.get_league(query)
+    }
+}
+
+/// League whose name matches all words of the query, otherwise the one with the team that
+/// matches the query best
+impl Tables for [League] {
+    fn table(&self, query: &str) -> Option<&League> {
+        let words = query_words(query);
+        self.iter()
+            .find(|league| matches_words(&words, &[league.name()]))
+            .or_else(|| {
+                self.iter()
+                    .filter_map(|league| {
+                        let score = league
+                            .entries
+                            .iter()
+                            .filter_map(|entry| similarity(query, &entry.team))
+                            .max()?;
+                        Some((score, league))
+                    })
+                    .max_by_key(|(score, _)| *score)
+                    .map(|(_, league)| league)
+            })
+    }
+}
+
+impl Tables for Vec<League> {
+    fn table(&self, query: &str) -> Option<&League> {
+        self.as_slice().table(query)
+    }
+}
+
+fn games(football: Football, query: &str) -> Reply {
+    Reply::Games(football.query(query))
+}
+
+fn team_games<'a>(
+    football: &'a Football,
+    team: &str,
+) -> impl Iterator<Item = (&'a Country, &'a Competition, &'a Game)> {
+    let words = query_words(team);
+    football.iter_games().filter(move |(_, _, game)| {
+        matches_words(&words, &[&game.home_team]) || matches_words(&words, &[&game.away_team])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::prelude::*;
+
+    #[test]
+    fn parsing() {
+        assert_eq!(
+            Command::parse("!NEXT  Genk "),
+            Ok(Command::Next(String::from("Genk")))
+        );
+        assert_eq!(Command::parse("!score"), Ok(Command::Score(String::new())));
+        assert_eq!(
+            Command::parse("what's the score?"),
+            Err(ParseCommandError::NotACommand)
+        );
+        assert_eq!(
+            Command::parse("!table").unwrap_err().to_string(),
+            "Usage: !table <league or team>"
+        );
+        assert_eq!(
+            Command::parse("!dance").unwrap_err(),
+            ParseCommandError::Unknown(String::from("dance"))
+        );
+    }
+
+    #[test]
+    fn replies() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 12, 0, 0).unwrap());
+        let game = |home: &str, away: &str, day, status| Game {
+            home_team: String::from(home),
+            away_team: String::from(away),
+            home_score: (status == GameStatus::Ended).then_some(2),
+            away_score: (status == GameStatus::Ended).then_some(0),
+            start_time: Utc.with_ymd_and_hms(2024, 3, day, 19, 30, 0).unwrap(),
+            status,
+        };
+        let football = Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![
+                        game("Genk", "Gent", 1, GameStatus::Ended),
+                        game("Anderlecht", "Genk", 2, GameStatus::Upcoming),
+                        game("Genk", "Club Brugge", 9, GameStatus::Upcoming),
+                    ],
+                }],
+            }],
+        };
+        let tables = League::from(include_str!("ranking/beebs/nations_league.html"));
+        let reply = |line| {
+            respond(line, &football, &tables, &clock)
+                .map(|reply| reply.lines(100, 3))
+                .unwrap_or_default()
+        };
+
+        assert_eq!(
+            reply("!next genk"),
+            vec!["Belgium First Division A: Anderlecht - Genk (in 7h 30m)"]
+        );
+        assert_eq!(
+            reply("!last gent")[0],
+            "Belgium First Division A: (FT) Genk 2-0 Gent (Fri 1 Mar)"
+        );
+        assert_eq!(reply("!today brugge"), vec!["No games found"]);
+        assert!(reply("!yesterday")[0].contains("Genk"));
+        let table = reply("!table iceland");
+        assert!(table.len() <= 3);
+        assert!(table.concat().contains("Iceland"));
+        assert_eq!(reply("!table narnia"), vec!["No table found for narnia"]);
+        assert!(reply("hello").is_empty());
+    }
+}
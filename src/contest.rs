@@ -0,0 +1,518 @@
+//! The predictions contest of the channel: guess the score before kickoff, get points once the
+//! game ends.
+
+use crate::clock::Clock;
+use crate::generic_structs::*;
+use crate::history::Outcome;
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+/// Points per prediction, only the best one that applies counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scoring {
+    pub exact_score: u32,
+    pub goal_difference: u32,
+    pub outcome: u32,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            exact_score: 3,
+            goal_difference: 2,
+            outcome: 1,
+        }
+    }
+}
+
+impl Scoring {
+    pub fn points(&self, predicted: (u8, u8), actual: (u8, u8)) -> u32 {
+        let difference = |(home, away): (u8, u8)| i16::from(home) - i16::from(away);
+        let outcome = |(home, away): (u8, u8)| Outcome::from_score(home, away);
+        if predicted == actual {
+            self.exact_score
+        } else if difference(predicted) == difference(actual) {
+            self.goal_difference
+        } else if outcome(predicted) == outcome(actual) {
+            self.outcome
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Prediction {
+    pub user: String,
+    pub game: GameId,
+    pub country: String,
+    pub competition: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub kickoff: DateTime<Utc>,
+    pub home_score: u8,
+    pub away_score: u8,
+    /// Final score, once the game ended
+    #[serde(default)]
+    pub result: Option<(u8, u8)>,
+}
+
+impl Prediction {
+    /// None until the game ended
+    pub fn points(&self, scoring: &Scoring) -> Option<u32> {
+        Some(scoring.points((self.home_score, self.away_score), self.result?))
+    }
+
+    /// Whether a game of this meeting kicking off then could be this one after it got moved
+    fn may_have_moved_to(&self, meeting: &str, kickoff: DateTime<Utc>) -> bool {
+        let own = GameId::meeting(
+            &self.country,
+            &self.competition,
+            &self.home_team,
+            &self.away_team,
+        );
+        own == meeting && (self.kickoff - kickoff).abs() <= RESCHEDULE_WINDOW
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PredictionError {
+    /// Game kicked off already, or is not going to be played
+    Locked,
+}
+
+impl fmt::Display for PredictionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PredictionError::Locked => write!(f, "Predictions for this game are closed"),
+        }
+    }
+}
+
+impl Error for PredictionError {}
+
+/// Which predictions a leaderboard counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Period {
+    /// ISO week of kickoff
+    Week {
+        year: i32,
+        week: u32,
+    },
+    /// Seasons run from July to June, named after the year they start in
+    Season(i32),
+    /// Competition name, matched case insensitive
+    Competition(String),
+    AllTime,
+}
+
+impl Period {
+    /// Week of the given moment
+    pub fn week_of(time: DateTime<Utc>) -> Self {
+        let week = time.iso_week();
+        Period::Week {
+            year: week.year(),
+            week: week.week(),
+        }
+    }
+
+    /// Season of the given moment
+    pub fn season_of(time: DateTime<Utc>) -> Self {
+        if time.month() >= 7 {
+            Period::Season(time.year())
+        } else {
+            Period::Season(time.year() - 1)
+        }
+    }
+
+    fn contains(&self, prediction: &Prediction) -> bool {
+        match self {
+            Period::Week { .. } => Self::week_of(prediction.kickoff) == *self,
+            Period::Season(_) => Self::season_of(prediction.kickoff) == *self,
+            Period::Competition(name) => prediction.competition.eq_ignore_ascii_case(name),
+            Period::AllTime => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub user: String,
+    pub points: u32,
+    /// Scored predictions, so for games that ended
+    pub predictions: usize,
+    pub exact_scores: usize,
+}
+
+impl fmt::Display for LeaderboardEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}pts ({} predictions, {} exact)",
+            self.user, self.points, self.predictions, self.exact_scores
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contest {
+    #[serde(default)]
+    pub scoring: Scoring,
+    predictions: Vec<Prediction>,
+}
+
+impl Contest {
+    pub fn new(scoring: Scoring) -> Self {
+        Self {
+            scoring,
+            predictions: vec![],
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Contest always serializes")
+    }
+
+    /// Empty contest if the file does not exist yet
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Self::from_json(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(path, self.to_json())?;
+        Ok(())
+    }
+
+    /// Takes the user's prediction for the game, replacing any earlier one that was not scored yet,
+    /// also one from before the game got moved. Locked from kickoff.
+    pub fn submit(
+        &mut self,
+        clock: &impl Clock,
+        user: &str,
+        country: &str,
+        competition: &str,
+        game: &Game,
+        (home_score, away_score): (u8, u8),
+    ) -> Result<(), PredictionError> {
+        if game.status != GameStatus::Upcoming || clock.now() >= game.start_time {
+            return Err(PredictionError::Locked);
+        }
+        let id = GameId::new(country, competition, game);
+        let meeting = GameId::meeting(country, competition, &game.home_team, &game.away_team);
+        self.predictions.retain(|prediction| {
+            let same_game =
+                prediction.game == id || prediction.may_have_moved_to(&meeting, game.start_time);
+            !(prediction.user == user && prediction.result.is_none() && same_game)
+        });
+        self.predictions.push(Prediction {
+            user: user.to_owned(),
+            game: id,
+            country: country.to_owned(),
+            competition: competition.to_owned(),
+            home_team: game.home_team.clone(),
+            away_team: game.away_team.clone(),
+            kickoff: game.start_time,
+            home_score,
+            away_score,
+            result: None,
+        });
+        Ok(())
+    }
+
+    /// Scores the predictions of games that ended in the snapshot. A game that got moved (see
+    /// [RESCHEDULE_WINDOW]) scores the predictions made for it before, unless another prediction
+    /// is for that very game. Returns the predictions that got scored just now.
+    pub fn update(&mut self, football: &Football) -> Vec<&Prediction> {
+        let mut finished = HashMap::new();
+        let mut meetings = vec![];
+        for (country, competition, game) in football.iter_games() {
+            if game.status != GameStatus::Ended {
+                continue;
+            }
+            if let (Some(home), Some(away)) = (game.home_score, game.away_score) {
+                let id = GameId::new(&country.name, &competition.name, game);
+                let meeting = GameId::meeting(
+                    &country.name,
+                    &competition.name,
+                    &game.home_team,
+                    &game.away_team,
+                );
+                meetings.push((meeting, game.start_time, id.clone()));
+                finished.insert(id, (home, away));
+            }
+        }
+        let predicted: HashSet<_> = self
+            .predictions
+            .iter()
+            .map(|prediction| (prediction.user.clone(), prediction.game.clone()))
+            .collect();
+        let mut scored = vec![];
+        for (idx, prediction) in self.predictions.iter_mut().enumerate() {
+            if prediction.result.is_some() {
+                continue;
+            }
+            if !finished.contains_key(&prediction.game) {
+                let moved = meetings.iter().find(|(meeting, kickoff, id)| {
+                    prediction.may_have_moved_to(meeting, *kickoff)
+                        && !predicted.contains(&(prediction.user.clone(), id.clone()))
+                });
+                if let Some((_, kickoff, id)) = moved {
+                    prediction.game = id.clone();
+                    prediction.kickoff = *kickoff;
+                }
+            }
+            if let Some(result) = finished.get(&prediction.game) {
+                prediction.result = Some(*result);
+                scored.push(idx);
+            }
+        }
+        scored
+            .into_iter()
+            .map(|idx| &self.predictions[idx])
+            .collect()
+    }
+
+    pub fn predictions(&self) -> &[Prediction] {
+        &self.predictions
+    }
+
+    /// Everything the user predicted, oldest kickoff first
+    pub fn predictions_of(&self, user: &str) -> Vec<&Prediction> {
+        let mut predictions: Vec<_> = self
+            .predictions
+            .iter()
+            .filter(|prediction| prediction.user == user)
+            .collect();
+        predictions.sort_by_key(|prediction| prediction.kickoff);
+        predictions
+    }
+
+    /// Users by points, then by exact scores. Only scored predictions count.
+    pub fn leaderboard(&self, period: &Period) -> Vec<LeaderboardEntry> {
+        let mut entries: Vec<LeaderboardEntry> = vec![];
+        for prediction in self.predictions.iter().filter(|p| period.contains(p)) {
+            let Some(points) = prediction.points(&self.scoring) else {
+                continue;
+            };
+            let idx = match entries.iter().position(|e| e.user == prediction.user) {
+                Some(idx) => idx,
+                None => {
+                    entries.push(LeaderboardEntry {
+                        user: prediction.user.clone(),
+                        points: 0,
+                        predictions: 0,
+                        exact_scores: 0,
+                    });
+                    entries.len() - 1
+                }
+            };
+            let entry = &mut entries[idx];
+            entry.points += points;
+            entry.predictions += 1;
+            if prediction.result == Some((prediction.home_score, prediction.away_score)) {
+                entry.exact_scores += 1;
+            }
+        }
+        entries.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(b.exact_scores.cmp(&a.exact_scores))
+                .then(a.user.cmp(&b.user))
+        });
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::prelude::*;
+
+    fn game(home: &str, away: &str, day: u32, score: Option<(u8, u8)>) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: score.map(|s| s.0),
+            away_score: score.map(|s| s.1),
+            start_time: Utc.with_ymd_and_hms(2024, 3, day, 19, 0, 0).unwrap(),
+            status: match score {
+                Some(_) => GameStatus::Ended,
+                None => GameStatus::Upcoming,
+            },
+        }
+    }
+
+    fn football(games: Vec<Game>) -> Football {
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games,
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn scoring() {
+        let scoring = Scoring::default();
+        assert_eq!(scoring.points((2, 1), (2, 1)), 3);
+        assert_eq!(scoring.points((1, 0), (2, 1)), 2);
+        assert_eq!(scoring.points((1, 1), (0, 0)), 2);
+        assert_eq!(scoring.points((3, 0), (2, 1)), 1);
+        assert_eq!(scoring.points((0, 1), (2, 1)), 0);
+    }
+
+    #[test]
+    fn playing_a_week() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        let genk = game("Genk", "Gent", 2, None);
+        let brugge = game("Club Brugge", "Anderlecht", 9, None);
+        let mut contest = Contest::default();
+        let submit = |contest: &mut Contest, user, game, score: (u8, u8)| {
+            contest.submit(&clock, user, "Belgium", "First Division A", game, score)
+        };
+        submit(&mut contest, "alice", &genk, (1, 1)).unwrap();
+        submit(&mut contest, "alice", &genk, (2, 1)).unwrap();
+        submit(&mut contest, "bob", &genk, (1, 0)).unwrap();
+        submit(&mut contest, "carol", &genk, (0, 2)).unwrap();
+        submit(&mut contest, "bob", &brugge, (2, 2)).unwrap();
+        assert_eq!(contest.predictions_of("alice").len(), 1);
+
+        let late = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 19, 0, 0).unwrap());
+        assert_eq!(
+            contest.submit(&late, "dave", "Belgium", "First Division A", &genk, (0, 0)),
+            Err(PredictionError::Locked)
+        );
+
+        let scored = contest.update(&football(vec![game("Genk", "Gent", 2, Some((2, 1)))]));
+        assert_eq!(scored.len(), 3);
+        // Scored once only
+        assert!(contest
+            .update(&football(vec![game("Genk", "Gent", 2, Some((2, 1)))]))
+            .is_empty());
+        contest.update(&football(vec![game(
+            "Club Brugge",
+            "Anderlecht",
+            9,
+            Some((1, 1)),
+        )]));
+
+        let week = Period::week_of(Utc.with_ymd_and_hms(2024, 2, 28, 0, 0, 0).unwrap());
+        let lines: Vec<_> = contest
+            .leaderboard(&week)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "alice 3pts (1 predictions, 1 exact)",
+                "bob 2pts (1 predictions, 0 exact)",
+                "carol 0pts (1 predictions, 0 exact)",
+            ]
+        );
+        let season = contest.leaderboard(&Period::Season(2023));
+        assert_eq!(season[0].user, "bob");
+        assert_eq!(season[0].points, 4);
+        assert_eq!(
+            contest.leaderboard(&Period::Competition(String::from("first division a")))[0],
+            season[0]
+        );
+        assert!(contest.leaderboard(&Period::Season(2024)).is_empty());
+
+        // Round trip through the file format
+        let loaded = Contest::from_json(&contest.to_json()).unwrap();
+        assert_eq!(loaded, contest);
+    }
+
+    #[test]
+    fn two_meetings_in_a_season() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        let regular_season = game("Genk", "Gent", 2, None);
+        let mut contest = Contest::default();
+        contest
+            .submit(
+                &clock,
+                "alice",
+                "Belgium",
+                "First Division A",
+                &regular_season,
+                (2, 1),
+            )
+            .unwrap();
+        contest.update(&football(vec![game("Genk", "Gent", 2, Some((2, 1)))]));
+
+        // The play-off rematch, a month and a half later
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 4, 1, 12, 0, 0).unwrap());
+        let mut rematch = game("Genk", "Gent", 2, None);
+        rematch.start_time = Utc.with_ymd_and_hms(2024, 4, 14, 16, 0, 0).unwrap();
+        contest
+            .submit(
+                &clock,
+                "alice",
+                "Belgium",
+                "First Division A",
+                &rematch,
+                (0, 0),
+            )
+            .unwrap();
+        let predictions = contest.predictions_of("alice");
+        assert_eq!(predictions.len(), 2);
+        assert_ne!(predictions[0].game, predictions[1].game);
+        assert_eq!(predictions[0].result, Some((2, 1)));
+        assert_eq!(predictions[1].result, None);
+    }
+
+    #[test]
+    fn moved_game() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        let mut contest = Contest::default();
+        let planned = game("Genk", "Gent", 2, None);
+        contest
+            .submit(
+                &clock,
+                "alice",
+                "Belgium",
+                "First Division A",
+                &planned,
+                (1, 0),
+            )
+            .unwrap();
+        contest
+            .submit(
+                &clock,
+                "bob",
+                "Belgium",
+                "First Division A",
+                &planned,
+                (0, 1),
+            )
+            .unwrap();
+        // Bob predicts again after the game moved a few days
+        let moved = game("Genk", "Gent", 6, None);
+        contest
+            .submit(&clock, "bob", "Belgium", "First Division A", &moved, (2, 2))
+            .unwrap();
+        assert_eq!(contest.predictions_of("bob").len(), 1);
+
+        let scored = contest.update(&football(vec![game("Genk", "Gent", 6, Some((1, 0)))]));
+        assert_eq!(scored.len(), 2);
+        let alice = &contest.predictions_of("alice")[0];
+        assert_eq!(alice.points(&contest.scoring), Some(3));
+        assert_eq!(alice.kickoff, moved.start_time);
+    }
+}
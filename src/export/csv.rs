@@ -0,0 +1,84 @@
+//! CSV (RFC 4180) export, header row first
+
+use super::{EntryRow, GameRow};
+use crate::generic_structs::*;
+use crate::ranking::beebs::League;
+
+impl Football {
+    /// One row per game, see [GameRow] for the columns
+    pub fn to_csv(&self) -> String {
+        to_csv(
+            GameRow::HEADER,
+            self.to_rows().iter().map(|row| row.fields()),
+        )
+    }
+}
+
+impl League {
+    /// One row per team, see [EntryRow] for the columns
+    pub fn to_csv(&self) -> String {
+        to_csv(
+            EntryRow::HEADER,
+            self.to_rows().iter().map(|row| row.fields()),
+        )
+    }
+}
+
+fn to_csv(header: &[&str], rows: impl Iterator<Item = Vec<String>>) -> String {
+    let mut result = String::new();
+    push_record(&mut result, header.iter().copied());
+    for row in rows {
+        push_record(&mut result, row.iter().map(|field| field.as_str()));
+    }
+    result
+}
+
+fn push_record<'a>(result: &mut String, fields: impl Iterator<Item = &'a str>) {
+    let fields: Vec<_> = fields.map(escape).collect();
+    result.push_str(&fields.join(","));
+    result.push_str("\r\n");
+}
+
+/// Quotes the field if needed, doubling any quotes inside
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    #[test]
+    fn games_to_csv() {
+        let football = Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("Cup, Final"),
+                    games: vec![Game {
+                        home_team: String::from("Union SG"),
+                        away_team: String::from("Antwerp"),
+                        home_score: Some(1),
+                        away_score: None,
+                        start_time: Utc.with_ymd_and_hms(2024, 5, 9, 18, 45, 0).unwrap(),
+                        status: GameStatus::Ongoing(String::from("HT")),
+                    }],
+                }],
+            }],
+        };
+        let csv = football.to_csv();
+        let lines: Vec<_> = csv.split("\r\n").collect();
+        assert_eq!(
+            lines[0],
+            "id,country,competition,home_team,away_team,home_score,away_score,status,kickoff"
+        );
+        assert!(lines[1]
+            .ends_with(",Belgium,\"Cup, Final\",Union SG,Antwerp,1,,HT,2024-05-09T18:45:00+00:00"));
+        assert_eq!(lines[2], "");
+    }
+}
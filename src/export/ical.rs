@@ -0,0 +1,316 @@
+//! iCalendar (RFC 5545) export so fixtures can be subscribed to from a calendar app.
+
+use crate::clock::{Clock, SystemClock};
+use crate::generic_structs::*;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+/// How long a game is assumed to take in the calendar
+const GAME_DURATION: chrono::Duration = chrono::Duration::hours(2);
+const DATETIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Holds [GameId::meeting], to recognise a moved game in the next export
+const MEETING_PROPERTY: &str = "X-FOOTBALL-MEETING";
+
+impl Football {
+    /// Every game as an event in one calendar. Query or filter first to limit it to, e.g., one
+    /// team. Event UIDs come from [GameId] so a calendar app updates events when it refetches
+    /// instead of adding them again.
+    pub fn to_ical(&self, calendar_name: &str) -> String {
+        self.to_ical_with(&SystemClock, calendar_name)
+    }
+
+    /// [Football::to_ical], with the export timestamp taken from clock
+    pub fn to_ical_with(&self, clock: &impl Clock, calendar_name: &str) -> String {
+        self.to_ical_updating(clock, calendar_name, "")
+    }
+
+    /// [Football::to_ical_with], for when the calendar was exported before. A game that moved to
+    /// another day (up to [RESCHEDULE_WINDOW]) keeps the UID it had in `previous`, so the calendar
+    /// app moves the event instead of showing the game twice.
+    pub fn to_ical_updating(
+        &self,
+        clock: &impl Clock,
+        calendar_name: &str,
+        previous: &str,
+    ) -> String {
+        let uids = uids(self, &previous_events(previous));
+        let stamp = clock.now().format(DATETIME_FORMAT).to_string();
+        let mut lines = vec![
+            String::from("BEGIN:VCALENDAR"),
+            String::from("VERSION:2.0"),
+            String::from("PRODID:-//ward//football//EN"),
+            String::from("CALSCALE:GREGORIAN"),
+            format!("X-WR-CALNAME:{}", escape(calendar_name)),
+        ];
+        for ((country, competition, game), uid) in self.iter_games().zip(uids) {
+            lines.push(String::from("BEGIN:VEVENT"));
+            lines.push(format!("UID:{}", uid));
+            lines.push(format!(
+                "{}:{}",
+                MEETING_PROPERTY,
+                GameId::meeting(
+                    &country.name,
+                    &competition.name,
+                    &game.home_team,
+                    &game.away_team
+                )
+            ));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!(
+                "DTSTART:{}",
+                game.start_time.format(DATETIME_FORMAT)
+            ));
+            lines.push(format!(
+                "DTEND:{}",
+                (game.start_time + GAME_DURATION).format(DATETIME_FORMAT)
+            ));
+            lines.push(format!("SUMMARY:{}", escape(&summary(game))));
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                escape(&description(country, competition, game))
+            ));
+            match game.status {
+                GameStatus::Cancelled => lines.push(String::from("STATUS:CANCELLED")),
+                GameStatus::Postponed => lines.push(String::from("STATUS:TENTATIVE")),
+                _ => lines.push(String::from("STATUS:CONFIRMED")),
+            }
+            lines.push(String::from("END:VEVENT"));
+        }
+        lines.push(String::from("END:VCALENDAR"));
+
+        let mut result = String::new();
+        for line in lines {
+            result.push_str(&fold(&line));
+            result.push_str("\r\n");
+        }
+        result
+    }
+}
+
+/// UID, meeting, and start of the events in an earlier export
+struct PreviousEvent {
+    uid: String,
+    meeting: String,
+    start: DateTime<Utc>,
+}
+
+fn previous_events(ical: &str) -> Vec<PreviousEvent> {
+    let unfolded = ical.replace("\r\n ", "");
+    let mut events = vec![];
+    for event in unfolded.split("BEGIN:VEVENT").skip(1) {
+        let property = |name: &str| {
+            event
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+        };
+        let start = property("DTSTART")
+            .and_then(|start| NaiveDateTime::parse_from_str(start, DATETIME_FORMAT).ok());
+        if let (Some(uid), Some(meeting), Some(start)) =
+            (property("UID"), property(MEETING_PROPERTY), start)
+        {
+            events.push(PreviousEvent {
+                uid: uid.to_owned(),
+                meeting: meeting.to_owned(),
+                start: start.and_utc(),
+            });
+        }
+    }
+    events
+}
+
+/// UID of every game, in [Football::iter_games] order. Those come from [GameId], unless the game
+/// is a moved one from the previous export. That is an event of the same meeting, close enough in
+/// time, whose own game is gone.
+fn uids(football: &Football, previous: &[PreviousEvent]) -> Vec<String> {
+    let games: Vec<_> = football
+        .iter_games()
+        .map(|(country, competition, game)| {
+            let id = format!(
+                "{}@football",
+                GameId::new(&country.name, &competition.name, game)
+            );
+            let meeting = GameId::meeting(
+                &country.name,
+                &competition.name,
+                &game.home_team,
+                &game.away_team,
+            );
+            (id, meeting, game.start_time)
+        })
+        .collect();
+    let mut taken: HashSet<_> = games.iter().map(|(id, _, _)| id.clone()).collect();
+    let known: HashMap<_, _> = previous.iter().map(|event| (&event.uid, event)).collect();
+    games
+        .into_iter()
+        .map(|(id, meeting, start)| {
+            if known.contains_key(&id) {
+                return id;
+            }
+            let moved = previous
+                .iter()
+                .filter(|event| event.meeting == meeting && !taken.contains(&event.uid))
+                .filter(|event| (event.start - start).abs() <= RESCHEDULE_WINDOW)
+                .min_by_key(|event| (event.start - start).abs());
+            match moved {
+                Some(event) => {
+                    taken.insert(event.uid.clone());
+                    event.uid.clone()
+                }
+                None => id,
+            }
+        })
+        .collect()
+}
+
+fn summary(game: &Game) -> String {
+    match (&game.status, game.home_score, game.away_score) {
+        (GameStatus::Ended, Some(home_score), Some(away_score)) => format!(
+            "{} {}-{} {}",
+            game.home_team, home_score, away_score, game.away_team
+        ),
+        _ => format!("{} - {}", game.home_team, game.away_team),
+    }
+}
+
+/// Competition and stage, and how it went once known
+fn description(country: &Country, competition: &Competition, game: &Game) -> String {
+    let mut description = format!("{} - {}", country.name, competition.name);
+    match (&game.status, game.home_score, game.away_score) {
+        (GameStatus::Ended, Some(home_score), Some(away_score)) => {
+            description.push_str(&format!("\nFinal score: {}-{}", home_score, away_score))
+        }
+        (GameStatus::Postponed, _, _) => description.push_str("\nPostponed"),
+        (GameStatus::Cancelled, _, _) => description.push_str("\nCancelled"),
+        _ => {}
+    }
+    description
+}
+
+/// TEXT values need backslashes, semicolons, commas, and newlines escaped
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Lines should not be longer than 75 bytes. Longer ones continue on the next line, starting with
+/// a space.
+fn fold(line: &str) -> String {
+    let mut result = String::new();
+    let mut current_length = 0;
+    for c in line.chars() {
+        // Continuation lines lose a byte to the leading space
+        if current_length + c.len_utf8() > 75 {
+            result.push_str("\r\n ");
+            current_length = 1;
+        }
+        result.push(c);
+        current_length += c.len_utf8();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::prelude::*;
+
+    fn football(status: GameStatus, home_score: Option<u8>, away_score: Option<u8>) -> Football {
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![Game {
+                        home_team: String::from("Anderlecht"),
+                        away_team: String::from("Club Brugge"),
+                        home_score,
+                        away_score,
+                        start_time: Utc.with_ymd_and_hms(2024, 3, 17, 17, 30, 0).unwrap(),
+                        status,
+                    }],
+                }],
+            }],
+        }
+    }
+
+    fn uid(ical: &str) -> &str {
+        ical.lines().find(|line| line.starts_with("UID:")).unwrap()
+    }
+
+    #[test]
+    fn same_uid_after_result() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 16, 12, 0, 0).unwrap());
+        let before = football(GameStatus::Upcoming, None, None).to_ical_with(&clock, "RSCA");
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 18, 12, 0, 0).unwrap());
+        let after = football(GameStatus::Ended, Some(2), Some(1)).to_ical_with(&clock, "RSCA");
+        assert_eq!(uid(&before), uid(&after));
+
+        assert!(before.contains("SUMMARY:Anderlecht - Club Brugge\r\n"));
+        assert!(before.contains("DTSTART:20240317T173000Z\r\n"));
+        assert!(after.contains("SUMMARY:Anderlecht 2-1 Club Brugge\r\n"));
+        assert!(after.contains("DESCRIPTION:Belgium - First Division A\\nFinal score: 2-1\r\n"));
+        assert!(after.contains("DTSTAMP:20240318T120000Z\r\n"));
+    }
+
+    #[test]
+    fn same_uid_after_rescheduling() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 16, 12, 0, 0).unwrap());
+        let before = football(GameStatus::Upcoming, None, None).to_ical_with(&clock, "RSCA");
+        let mut moved = football(GameStatus::Upcoming, None, None);
+        moved.countries[0].competitions[0].games[0].start_time =
+            Utc.with_ymd_and_hms(2024, 3, 27, 18, 45, 0).unwrap();
+        assert_ne!(uid(&moved.to_ical_with(&clock, "RSCA")), uid(&before));
+
+        let after = moved.to_ical_updating(&clock, "RSCA", &before);
+        assert_eq!(uid(&after), uid(&before));
+        assert!(after.contains("DTSTART:20240327T184500Z\r\n"));
+        // And it sticks for the export after that
+        let again = moved.to_ical_updating(&clock, "RSCA", &after);
+        assert_eq!(uid(&again), uid(&before));
+    }
+
+    #[test]
+    fn two_meetings_in_a_season() {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 16, 12, 0, 0).unwrap());
+        let regular_season = football(GameStatus::Ended, Some(2), Some(1));
+        let mut both = football(GameStatus::Upcoming, None, None);
+        let rematch = &mut both.countries[0].competitions[0].games[0];
+        rematch.start_time = Utc.with_ymd_and_hms(2024, 4, 21, 18, 30, 0).unwrap();
+        let rematch = rematch.clone();
+        both.countries[0].competitions[0].games.insert(
+            0,
+            regular_season.countries[0].competitions[0].games[0].clone(),
+        );
+
+        let uids = |ical: &str| -> Vec<String> {
+            ical.lines()
+                .filter(|line| line.starts_with("UID:"))
+                .map(String::from)
+                .collect()
+        };
+        let previous = regular_season.to_ical_with(&clock, "RSCA");
+        let ical = both.to_ical_updating(&clock, "RSCA", &previous);
+        let found = uids(&ical);
+        assert_eq!(found.len(), 2);
+        assert_ne!(found[0], found[1]);
+        assert_eq!(found[0], uid(&previous));
+
+        // Only the rematch left, but too far from the first game to be taken for it
+        let mut later = football(GameStatus::Upcoming, None, None);
+        later.countries[0].competitions[0].games[0] = rematch;
+        let ical = later.to_ical_updating(&clock, "RSCA", &previous);
+        assert_eq!(uids(&ical), vec![found[1].clone()]);
+    }
+
+    #[test]
+    fn long_lines_folded() {
+        let folded = fold(&"a".repeat(160));
+        let lines: Vec<_> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), "a".repeat(160));
+    }
+}
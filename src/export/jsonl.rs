@@ -0,0 +1,46 @@
+//! JSON Lines export, one JSON object per line
+
+use crate::generic_structs::*;
+use crate::ranking::beebs::League;
+
+impl Football {
+    /// One line per game, see [crate::GameRow] for the fields
+    pub fn to_jsonl(&self) -> String {
+        to_jsonl(&self.to_rows())
+    }
+}
+
+impl League {
+    /// One line per team, see [crate::EntryRow] for the fields
+    pub fn to_jsonl(&self) -> String {
+        to_jsonl(&self.to_rows())
+    }
+}
+
+fn to_jsonl<T: serde::Serialize>(rows: &[T]) -> String {
+    let mut result = String::new();
+    for row in rows {
+        result.push_str(&serde_json::to_string(row).expect("Rows always serialize"));
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::EntryRow;
+
+    #[test]
+    fn league_to_jsonl() {
+        let content = include_str!("../ranking/beebs/epl.html");
+        let leagues = League::from(content);
+        let jsonl = leagues[0].to_jsonl();
+        assert_eq!(jsonl.lines().count(), leagues[0].entries.len());
+        let third: EntryRow = serde_json::from_str(jsonl.lines().nth(2).unwrap()).unwrap();
+        assert_eq!(third.team, "Arsenal");
+        assert_eq!(third.played, third.win + third.draw + third.lose);
+        // Keys in column order
+        assert!(jsonl.starts_with("{\"league\":"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Getting games out of the crate in formats other programs understand.
+
+mod csv;
+mod ical;
+mod jsonl;
+
+use crate::generic_structs::*;
+use crate::history::PlayedGame;
+use crate::ranking::beebs::{Entry, League};
+use serde::{Deserialize, Serialize};
+
+/// One game in a flat shape, as used for a line of CSV or JSON. Field order is the column order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRow {
+    pub id: String,
+    pub country: String,
+    pub competition: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: Option<u8>,
+    pub away_score: Option<u8>,
+    /// upcoming, ended, postponed, cancelled, or the game time for ongoing games (e.g., 45')
+    pub status: String,
+    /// RFC 3339, UTC
+    pub kickoff: String,
+}
+
+impl GameRow {
+    const HEADER: &'static [&'static str] = &[
+        "id",
+        "country",
+        "competition",
+        "home_team",
+        "away_team",
+        "home_score",
+        "away_score",
+        "status",
+        "kickoff",
+    ];
+
+    pub fn new(country: &Country, competition: &Competition, game: &Game) -> Self {
+        Self {
+            id: GameId::new(&country.name, &competition.name, game).to_string(),
+            country: country.name.clone(),
+            competition: competition.name.clone(),
+            home_team: game.home_team.clone(),
+            away_team: game.away_team.clone(),
+            home_score: game.home_score,
+            away_score: game.away_score,
+            status: status_to_str(&game.status).to_owned(),
+            kickoff: game.start_time.to_rfc3339(),
+        }
+    }
+
+    /// Back into a game, if it is a finished one with a score. This is how exported results get
+    /// used as history again.
+    pub fn to_played_game(&self) -> Option<PlayedGame> {
+        let status = status_from_str(&self.status);
+        if status != GameStatus::Ended {
+            return None;
+        }
+        let start_time = chrono::DateTime::parse_from_rfc3339(&self.kickoff).ok()?;
+        Some(PlayedGame {
+            country: self.country.clone(),
+            competition: self.competition.clone(),
+            game: Game {
+                home_team: self.home_team.clone(),
+                away_team: self.away_team.clone(),
+                home_score: Some(self.home_score?),
+                away_score: Some(self.away_score?),
+                start_time: start_time.with_timezone(&chrono::Utc),
+                status,
+            },
+        })
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.country.clone(),
+            self.competition.clone(),
+            self.home_team.clone(),
+            self.away_team.clone(),
+            optional_to_string(self.home_score),
+            optional_to_string(self.away_score),
+            self.status.clone(),
+            self.kickoff.clone(),
+        ]
+    }
+}
+
+/// One entry of a table in a flat shape, as used for a line of CSV or JSON. Field order is the
+/// column order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryRow {
+    pub league: String,
+    pub rank: i8,
+    pub team: String,
+    pub played: i8,
+    pub win: i8,
+    pub draw: i8,
+    pub lose: i8,
+    pub gf: i8,
+    pub ga: i8,
+    pub gd: i8,
+    pub points: i8,
+}
+
+impl EntryRow {
+    const HEADER: &'static [&'static str] = &[
+        "league", "rank", "team", "played", "win", "draw", "lose", "gf", "ga", "gd", "points",
+    ];
+
+    pub fn new(league: &League, entry: &Entry) -> Self {
+        Self {
+            league: league.name.clone(),
+            rank: entry.rank,
+            team: entry.team.clone(),
+            played: entry.win + entry.draw + entry.lose,
+            win: entry.win,
+            draw: entry.draw,
+            lose: entry.lose,
+            gf: entry.gf,
+            ga: entry.ga,
+            gd: entry.gf - entry.ga,
+            points: entry.points,
+        }
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.league.clone(),
+            self.rank.to_string(),
+            self.team.clone(),
+            self.played.to_string(),
+            self.win.to_string(),
+            self.draw.to_string(),
+            self.lose.to_string(),
+            self.gf.to_string(),
+            self.ga.to_string(),
+            self.gd.to_string(),
+            self.points.to_string(),
+        ]
+    }
+}
+
+impl Football {
+    pub fn to_rows(&self) -> Vec<GameRow> {
+        self.iter_games()
+            .map(|(country, competition, game)| GameRow::new(country, competition, game))
+            .collect()
+    }
+}
+
+impl League {
+    pub fn to_rows(&self) -> Vec<EntryRow> {
+        self.entries
+            .iter()
+            .map(|entry| EntryRow::new(self, entry))
+            .collect()
+    }
+}
+
+fn status_to_str(status: &GameStatus) -> &str {
+    match status {
+        GameStatus::Upcoming => "upcoming",
+        GameStatus::Ongoing(t) => t,
+        GameStatus::Ended => "ended",
+        GameStatus::Postponed => "postponed",
+        GameStatus::Cancelled => "cancelled",
+    }
+}
+
+fn status_from_str(status: &str) -> GameStatus {
+    match status {
+        "upcoming" => GameStatus::Upcoming,
+        "ended" => GameStatus::Ended,
+        "postponed" => GameStatus::Postponed,
+        "cancelled" => GameStatus::Cancelled,
+        t => GameStatus::Ongoing(t.to_owned()),
+    }
+}
+
+fn optional_to_string(value: Option<u8>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
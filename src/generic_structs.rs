@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Container struct for all football scores
@@ -75,3 +76,69 @@ pub enum GameStatus {
     Cancelled,
     // Other(String),
 }
+
+impl Football {
+    /// Every game along with the country and competition it is part of
+    pub fn iter_games(&self) -> impl Iterator<Item = (&Country, &Competition, &Game)> {
+        self.countries.iter().flat_map(|country| {
+            country.competitions.iter().flat_map(move |competition| {
+                competition
+                    .games
+                    .iter()
+                    .map(move |game| (country, competition, game))
+            })
+        })
+    }
+}
+
+/// Identifies a game across snapshots: same country, competition, teams, and day of kickoff (UTC).
+/// Kickoff time itself is left out since that tends to move around a bit. The day tells apart
+/// meetings of the same teams, like a play-off rematch. A game moved to another day gets another
+/// id, [GameId::meeting] and [RESCHEDULE_WINDOW] help to recognise it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GameId(String);
+
+/// Games of the same teams in the same competition that kick off at most this far apart are taken
+/// to be one game that got moved
+pub const RESCHEDULE_WINDOW: chrono::Duration = chrono::Duration::days(14);
+
+impl GameId {
+    pub fn new(country: &str, competition: &str, game: &Game) -> Self {
+        let key = format!(
+            "{}|{}|{}|{}|{}",
+            country,
+            competition,
+            game.home_team,
+            game.away_team,
+            game.start_time.format("%Y%m%d")
+        );
+        GameId(fnv1a(&key))
+    }
+
+    /// What stays the same when a game gets moved: country, competition, and teams
+    pub fn meeting(country: &str, competition: &str, home_team: &str, away_team: &str) -> String {
+        fnv1a(&format!(
+            "{}|{}|{}|{}",
+            country, competition, home_team, away_team
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// FNV-1a, the std hashers are not guaranteed to stay the same between Rust versions.
+fn fnv1a(key: &str) -> String {
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+impl fmt::Display for GameId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,183 @@
+//! Form guide of a team, from its most recent finished games
+
+use super::{resolve_team, PlayedGame};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    pub fn from_score(goals_for: u8, goals_against: u8) -> Self {
+        match goals_for.cmp(&goals_against) {
+            std::cmp::Ordering::Greater => Outcome::Win,
+            std::cmp::Ordering::Equal => Outcome::Draw,
+            std::cmp::Ordering::Less => Outcome::Loss,
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        match self {
+            Outcome::Win => 3,
+            Outcome::Draw => 1,
+            Outcome::Loss => 0,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "W"),
+            Outcome::Draw => write!(f, "D"),
+            Outcome::Loss => write!(f, "L"),
+        }
+    }
+}
+
+/// One game as seen from the team whose form it is
+#[derive(Debug, Clone)]
+pub struct FormResult {
+    pub outcome: Outcome,
+    pub goals_for: u8,
+    pub goals_against: u8,
+    pub opponent: String,
+    pub at_home: bool,
+    pub played: PlayedGame,
+}
+
+#[derive(Debug, Clone)]
+pub struct Form {
+    pub team: String,
+    /// Oldest first, so the last one is the most recent game
+    pub results: Vec<FormResult>,
+}
+
+impl Form {
+    /// The `last` most recent finished games of the team. Team name is looked up like
+    /// [crate::Football::query] does it (see [resolve_team]). None if the team is not found.
+    pub fn compute(games: &[PlayedGame], team: &str, last: usize) -> Option<Self> {
+        let team = resolve_team(games, team)?;
+        let mut results: Vec<_> = games
+            .iter()
+            .filter_map(|played| {
+                let game = &played.game;
+                let (home_score, away_score) = (game.home_score?, game.away_score?);
+                let (at_home, goals_for, goals_against, opponent) = if game.home_team == team {
+                    (true, home_score, away_score, &game.away_team)
+                } else if game.away_team == team {
+                    (false, away_score, home_score, &game.home_team)
+                } else {
+                    return None;
+                };
+                Some(FormResult {
+                    outcome: Outcome::from_score(goals_for, goals_against),
+                    goals_for,
+                    goals_against,
+                    opponent: opponent.to_owned(),
+                    at_home,
+                    played: played.clone(),
+                })
+            })
+            .collect();
+        results.sort_by_key(|result| result.played.game.start_time);
+        let skip = results.len().saturating_sub(last);
+        results.drain(..skip);
+        Some(Form { team, results })
+    }
+
+    /// Compact form for chat, e.g., "WWDLW". Most recent game last.
+    pub fn compact(&self) -> String {
+        self.results
+            .iter()
+            .map(|result| result.outcome.to_string())
+            .collect()
+    }
+
+    pub fn points_per_game(&self) -> f64 {
+        let points: u32 = self.results.iter().map(|r| r.outcome.points()).sum();
+        self.per_game(points)
+    }
+
+    /// (scored, conceded) per game
+    pub fn goals_per_game(&self) -> (f64, f64) {
+        let scored: u32 = self.results.iter().map(|r| u32::from(r.goals_for)).sum();
+        let conceded: u32 = self
+            .results
+            .iter()
+            .map(|r| u32::from(r.goals_against))
+            .sum();
+        (self.per_game(scored), self.per_game(conceded))
+    }
+
+    fn per_game(&self, total: u32) -> f64 {
+        if self.results.is_empty() {
+            0.0
+        } else {
+            f64::from(total) / self.results.len() as f64
+        }
+    }
+}
+
+impl fmt::Display for Form {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (scored, conceded) = self.goals_per_game();
+        write!(
+            f,
+            "{} form {} ({:.2} ppg, goals {:.1}-{:.1} per game)",
+            self.team,
+            self.compact(),
+            self.points_per_game(),
+            scored,
+            conceded
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic_structs::*;
+    use chrono::prelude::*;
+
+    fn played(home: &str, away: &str, score: (u8, u8), day: u32) -> PlayedGame {
+        PlayedGame {
+            country: String::from("Belgium"),
+            competition: String::from("First Division A"),
+            game: Game {
+                home_team: home.to_owned(),
+                away_team: away.to_owned(),
+                home_score: Some(score.0),
+                away_score: Some(score.1),
+                start_time: Utc.with_ymd_and_hms(2024, 3, day, 19, 0, 0).unwrap(),
+                status: GameStatus::Ended,
+            },
+        }
+    }
+
+    #[test]
+    fn last_five() {
+        let games = vec![
+            played("Genk", "Gent", (1, 0), 9),
+            played("Gent", "Antwerp", (3, 1), 2),
+            played("Genk", "Anderlecht", (2, 2), 7),
+            played("Club Brugge", "Genk", (0, 1), 1),
+            played("Genk", "Standard", (0, 1), 5),
+            played("Westerlo", "Genk", (0, 4), 3),
+            played("Cercle Brugge", "Genk", (1, 2), 8),
+        ];
+        let form = Form::compute(&games, "genk", 5).unwrap();
+        assert_eq!(form.compact(), "WLDWW");
+        assert_eq!(form.results[1].opponent, "Standard");
+        assert!(form.results[1].at_home);
+        assert!((form.points_per_game() - 2.0).abs() < 1e-9);
+        assert_eq!(form.goals_per_game(), (9.0 / 5.0, 4.0 / 5.0));
+        assert_eq!(
+            form.to_string(),
+            "Genk form WLDWW (2.00 ppg, goals 1.8-0.8 per game)"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! "When did these two last meet and how did it go"
+
+use super::{resolve_team, PlayedGame};
+use std::fmt;
+
+/// How all meetings between two teams went, from the point of view of the first team
+#[derive(Debug, Clone)]
+pub struct HeadToHead {
+    pub team: String,
+    pub opponent: String,
+    pub overall: HeadToHeadRecord,
+    /// Only the meetings where team played at home
+    pub at_home: HeadToHeadRecord,
+    /// Only the meetings where team played away
+    pub away: HeadToHeadRecord,
+    /// Most recent first
+    pub last_meetings: Vec<PlayedGame>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeadToHeadRecord {
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub goals_for: u32,
+    pub goals_against: u32,
+}
+
+impl HeadToHeadRecord {
+    fn add(&mut self, goals_for: u8, goals_against: u8) {
+        self.played += 1;
+        self.goals_for += u32::from(goals_for);
+        self.goals_against += u32::from(goals_against);
+        match goals_for.cmp(&goals_against) {
+            std::cmp::Ordering::Greater => self.wins += 1,
+            std::cmp::Ordering::Equal => self.draws += 1,
+            std::cmp::Ordering::Less => self.losses += 1,
+        }
+    }
+}
+
+impl HeadToHead {
+    /// Team names are looked up in games the way [crate::Football::query] matches them (see
+    /// [resolve_team]). Keeps the `last` most recent meetings. None if either team is not found
+    /// or both queries end up at the same team.
+    pub fn compute(games: &[PlayedGame], team: &str, opponent: &str, last: usize) -> Option<Self> {
+        let team = resolve_team(games, team)?;
+        let opponent = resolve_team(games, opponent)?;
+        if team == opponent {
+            return None;
+        }
+        let mut result = HeadToHead {
+            team,
+            opponent,
+            overall: HeadToHeadRecord::default(),
+            at_home: HeadToHeadRecord::default(),
+            away: HeadToHeadRecord::default(),
+            last_meetings: vec![],
+        };
+        let mut meetings = vec![];
+        for played in games {
+            let game = &played.game;
+            let (home_score, away_score) = match (game.home_score, game.away_score) {
+                (Some(home_score), Some(away_score)) => (home_score, away_score),
+                _ => continue,
+            };
+            if game.home_team == result.team && game.away_team == result.opponent {
+                result.overall.add(home_score, away_score);
+                result.at_home.add(home_score, away_score);
+            } else if game.home_team == result.opponent && game.away_team == result.team {
+                result.overall.add(away_score, home_score);
+                result.away.add(away_score, home_score);
+            } else {
+                continue;
+            }
+            meetings.push(played.clone());
+        }
+        meetings.sort_by_key(|played| std::cmp::Reverse(played.game.start_time));
+        meetings.truncate(last);
+        result.last_meetings = meetings;
+        Some(result)
+    }
+}
+
+impl fmt::Display for HeadToHead {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{team} vs {opponent}: {played} played, {wins}W {draws}D {losses}L, goals {gf}-{ga}",
+            team = self.team,
+            opponent = self.opponent,
+            played = self.overall.played,
+            wins = self.overall.wins,
+            draws = self.overall.draws,
+            losses = self.overall.losses,
+            gf = self.overall.goals_for,
+            ga = self.overall.goals_against
+        )?;
+        if let Some(last) = self.last_meetings.first() {
+            write!(
+                f,
+                ". Last: {} {} {}",
+                last.game.start_time.format("%Y-%m-%d"),
+                last.competition,
+                last.game
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic_structs::*;
+    use chrono::prelude::*;
+
+    fn played(home: &str, away: &str, score: (u8, u8), year: i32) -> PlayedGame {
+        PlayedGame {
+            country: String::from("Belgium"),
+            competition: String::from("First Division A"),
+            game: Game {
+                home_team: home.to_owned(),
+                away_team: away.to_owned(),
+                home_score: Some(score.0),
+                away_score: Some(score.1),
+                start_time: Utc.with_ymd_and_hms(year, 3, 1, 19, 0, 0).unwrap(),
+                status: GameStatus::Ended,
+            },
+        }
+    }
+
+    fn games() -> Vec<PlayedGame> {
+        vec![
+            played("Anderlecht", "Club Brugge", (2, 1), 2021),
+            played("Club Brugge", "Anderlecht", (3, 0), 2022),
+            played("Anderlecht", "Club Brugge", (1, 1), 2023),
+            played("Anderlecht", "Genk", (4, 0), 2023),
+            played("Club Brugge", "Anderlecht", (0, 2), 2024),
+        ]
+    }
+
+    #[test]
+    fn record_and_splits() {
+        let h2h = HeadToHead::compute(&games(), "anderlecht", "brugge", 2).unwrap();
+        assert_eq!(h2h.team, "Anderlecht");
+        assert_eq!(h2h.opponent, "Club Brugge");
+        assert_eq!(
+            h2h.overall,
+            HeadToHeadRecord {
+                played: 4,
+                wins: 2,
+                draws: 1,
+                losses: 1,
+                goals_for: 5,
+                goals_against: 5,
+            }
+        );
+        assert_eq!(h2h.at_home.wins, 1);
+        assert_eq!(h2h.at_home.draws, 1);
+        assert_eq!(h2h.away.losses, 1);
+        assert_eq!(h2h.last_meetings.len(), 2);
+        assert_eq!(h2h.last_meetings[0].game.start_time.year(), 2024);
+        assert_eq!(
+            h2h.to_string(),
+            "Anderlecht vs Club Brugge: 4 played, 2W 1D 1L, goals 5-5. \
+            Last: 2024-03-01 First Division A (FT) Club Brugge 0-2 Anderlecht"
+        );
+    }
+
+    #[test]
+    fn unknown_or_same_team() {
+        assert!(HeadToHead::compute(&games(), "anderlecht", "standard", 5).is_none());
+        assert!(HeadToHead::compute(&games(), "anderlecht", "ANDERLECHT", 5).is_none());
+    }
+}
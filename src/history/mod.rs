@@ -0,0 +1,141 @@
+//! Keeping results around after they drop out of the livescore window. The `sqlite` feature
+//! adds `HistoryStore`, a database to keep them in.
+
+mod form;
+mod head_to_head;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use form::{Form, FormResult, Outcome};
+pub use head_to_head::{HeadToHead, HeadToHeadRecord};
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::{HistoryQuery, HistoryStore};
+
+use crate::export::GameRow;
+use crate::generic_structs::*;
+use crate::search::{matches_words, query_words};
+use std::collections::{HashMap, HashSet};
+
+/// A game along with the country and competition it was played in, so it can stand on its own
+/// outside of a Football.
+#[derive(Debug, Clone)]
+pub struct PlayedGame {
+    pub country: String,
+    pub competition: String,
+    pub game: Game,
+}
+
+impl PlayedGame {
+    pub fn id(&self) -> GameId {
+        GameId::new(&self.country, &self.competition, &self.game)
+    }
+
+    /// Reads back finished games from a JSON Lines export (see [Football::to_jsonl]). Anything
+    /// that was not finished at the time of export is skipped.
+    pub fn from_jsonl(content: &str) -> Result<Vec<Self>, serde_json::Error> {
+        let mut result = vec![];
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let row: GameRow = serde_json::from_str(line)?;
+            result.extend(row.to_played_game());
+        }
+        Ok(result)
+    }
+}
+
+/// Finished games of several snapshots (e.g., fetched a day apart), every game only once
+pub fn collect_finished(snapshots: &[Football]) -> Vec<PlayedGame> {
+    let mut seen = HashSet::new();
+    snapshots
+        .iter()
+        .flat_map(|snapshot| snapshot.finished_games())
+        .filter(|played| seen.insert(played.id()))
+        .collect()
+}
+
+/// Finds the team name in games that the query refers to, matching like [Football::query] does.
+/// If several teams match, an exact name wins, otherwise goes with the one that played most.
+pub fn resolve_team(games: &[PlayedGame], query: &str) -> Option<String> {
+    let exact = games
+        .iter()
+        .flat_map(|played| [&played.game.home_team, &played.game.away_team])
+        .find(|team| team.eq_ignore_ascii_case(query.trim()));
+    if let Some(team) = exact {
+        return Some(team.to_owned());
+    }
+    let words = query_words(query);
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for played in games {
+        for team in [&played.game.home_team, &played.game.away_team] {
+            if matches_words(&words, &[team]) {
+                *counts.entry(team).or_default() += 1;
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .max_by(|(a_name, a_count), (b_name, b_count)| {
+            a_count.cmp(b_count).then_with(|| b_name.cmp(a_name))
+        })
+        .map(|(name, _)| name.to_owned())
+}
+
+impl Football {
+    /// All games that ended and have a score
+    pub fn finished_games(&self) -> Vec<PlayedGame> {
+        self.iter_games()
+            .filter(|(_, _, game)| {
+                game.status == GameStatus::Ended
+                    && game.home_score.is_some()
+                    && game.away_score.is_some()
+            })
+            .map(|(country, competition, game)| PlayedGame {
+                country: country.name.clone(),
+                competition: competition.name.clone(),
+                game: game.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn snapshot(status: GameStatus) -> Football {
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("Cup"),
+                    games: vec![Game {
+                        home_team: String::from("Union SG"),
+                        away_team: String::from("Antwerp"),
+                        home_score: Some(1),
+                        away_score: Some(0),
+                        start_time: Utc.with_ymd_and_hms(2024, 5, 9, 18, 45, 0).unwrap(),
+                        status,
+                    }],
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn finished_from_snapshots_and_exports() {
+        let snapshots = vec![
+            snapshot(GameStatus::Ongoing(String::from("80'"))),
+            snapshot(GameStatus::Ended),
+            snapshot(GameStatus::Ended),
+        ];
+        let collected = collect_finished(&snapshots);
+        assert_eq!(collected.len(), 1);
+
+        let exported = snapshots[0].to_jsonl() + &snapshots[1].to_jsonl();
+        let imported = PlayedGame::from_jsonl(&exported).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].id(), collected[0].id());
+        assert_eq!(imported[0].game.start_time, collected[0].game.start_time);
+    }
+}
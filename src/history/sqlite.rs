@@ -0,0 +1,298 @@
+//! Finished games stored in a local SQLite database.
+
+use super::PlayedGame;
+use crate::generic_structs::*;
+use crate::search::query_words;
+use chrono::prelude::*;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+use std::path::Path;
+
+/// Finished games, one row per [GameId]. Feed it every Football snapshot that comes in.
+pub struct HistoryStore {
+    connection: Connection,
+}
+
+impl HistoryStore {
+    /// Opens (or creates) the database at path
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, rusqlite::Error> {
+        Self::setup(Connection::open(path)?)
+    }
+
+    /// Nothing is written to disk, mostly for testing
+    pub fn open_in_memory() -> Result<Self, rusqlite::Error> {
+        Self::setup(Connection::open_in_memory()?)
+    }
+
+    fn setup(connection: Connection) -> Result<Self, rusqlite::Error> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS games (
+                id TEXT PRIMARY KEY,
+                country TEXT NOT NULL,
+                competition TEXT NOT NULL,
+                home_team TEXT NOT NULL,
+                away_team TEXT NOT NULL,
+                home_score INTEGER NOT NULL,
+                away_score INTEGER NOT NULL,
+                kickoff INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS games_kickoff ON games (kickoff);",
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// Stores the finished games of a snapshot. Games that were stored before are left alone.
+    /// Returns how many games were new.
+    pub fn record(&mut self, football: &Football) -> Result<usize, rusqlite::Error> {
+        self.record_games(&football.finished_games())
+    }
+
+    /// Same as [HistoryStore::record], for games that came from elsewhere (e.g., an export)
+    pub fn record_games(&mut self, games: &[PlayedGame]) -> Result<usize, rusqlite::Error> {
+        let transaction = self.connection.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut statement = transaction.prepare(
+                "INSERT INTO games
+                (id, country, competition, home_team, away_team, home_score, away_score, kickoff)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT (id) DO NOTHING",
+            )?;
+            for played in games {
+                let (home_score, away_score) =
+                    match (played.game.home_score, played.game.away_score) {
+                        (Some(home_score), Some(away_score)) => (home_score, away_score),
+                        _ => continue,
+                    };
+                inserted += statement.execute(params![
+                    played.id().as_str(),
+                    played.country,
+                    played.competition,
+                    played.game.home_team,
+                    played.game.away_team,
+                    home_score,
+                    away_score,
+                    played.game.start_time.timestamp(),
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(inserted)
+    }
+
+    /// Stored games matching the query, oldest first
+    pub fn results(&self, query: &HistoryQuery) -> Result<Vec<PlayedGame>, rusqlite::Error> {
+        let (conditions, values) = query.to_sql();
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT country, competition, home_team, away_team, home_score, away_score, kickoff
+            FROM games
+            WHERE {}
+            ORDER BY kickoff, id",
+            conditions
+        ))?;
+        let rows = statement.query_map(params_from_iter(values), |row| {
+            Ok(PlayedGame {
+                country: row.get(0)?,
+                competition: row.get(1)?,
+                game: Game {
+                    home_team: row.get(2)?,
+                    away_team: row.get(3)?,
+                    home_score: Some(row.get(4)?),
+                    away_score: Some(row.get(5)?),
+                    start_time: Utc
+                        .timestamp_opt(row.get(6)?, 0)
+                        .single()
+                        .unwrap_or_default(),
+                    status: GameStatus::Ended,
+                },
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn results_for_team(&self, team: &str) -> Result<Vec<PlayedGame>, rusqlite::Error> {
+        self.results(&HistoryQuery::new().team(team))
+    }
+
+    pub fn results_for_competition(
+        &self,
+        competition: &str,
+    ) -> Result<Vec<PlayedGame>, rusqlite::Error> {
+        self.results(&HistoryQuery::new().competition(competition))
+    }
+
+    pub fn results_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PlayedGame>, rusqlite::Error> {
+        self.results(&HistoryQuery::new().from(from).to(to))
+    }
+}
+
+/// Which stored games to return. Team and competition are matched word by word like
+/// [Football::query] does, competition against both country and competition name.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    team: Option<Vec<String>>,
+    competition: Option<Vec<String>>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+impl HistoryQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Games where either side matches
+    pub fn team(mut self, team: &str) -> Self {
+        self.team = Some(query_words(team));
+        self
+    }
+
+    pub fn competition(mut self, competition: &str) -> Self {
+        self.competition = Some(query_words(competition));
+        self
+    }
+
+    /// Kickoff at or after
+    pub fn from(mut self, from: DateTime<Utc>) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Kickoff before
+    pub fn to(mut self, to: DateTime<Utc>) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// WHERE clause and its parameters. Words are nothing but letters, so they are safe to put
+    /// in a LIKE pattern, which ignores case like [Football::query] does.
+    fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut conditions = vec![];
+        let mut values = vec![];
+        let mut all_words = |words: &[String], columns: &[&str]| {
+            let mut matched = vec![];
+            for word in words {
+                values.push(Value::Text(format!("%{}%", word)));
+                let columns: Vec<_> = columns
+                    .iter()
+                    .map(|column| format!("{} LIKE ?{}", column, values.len()))
+                    .collect();
+                matched.push(format!("({})", columns.join(" OR ")));
+            }
+            if matched.is_empty() {
+                String::from("1")
+            } else {
+                format!("({})", matched.join(" AND "))
+            }
+        };
+        if let Some(words) = &self.team {
+            let home = all_words(words, &["home_team"]);
+            let away = all_words(words, &["away_team"]);
+            conditions.push(format!("({} OR {})", home, away));
+        }
+        if let Some(words) = &self.competition {
+            conditions.push(all_words(words, &["country", "competition"]));
+        }
+        if let Some(from) = self.from {
+            values.push(Value::Integer(from.timestamp()));
+            conditions.push(format!("kickoff >= ?{}", values.len()));
+        }
+        if let Some(to) = self.to {
+            values.push(Value::Integer(to.timestamp()));
+            conditions.push(format!("kickoff < ?{}", values.len()));
+        }
+        if conditions.is_empty() {
+            conditions.push(String::from("1"));
+        }
+        (conditions.join(" AND "), values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(home: &str, away: &str, score: (u8, u8), day: u32, status: GameStatus) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: Some(score.0),
+            away_score: Some(score.1),
+            start_time: Utc.with_ymd_and_hms(2024, 3, day, 19, 0, 0).unwrap(),
+            status,
+        }
+    }
+
+    fn snapshot() -> Football {
+        Football {
+            countries: vec![
+                Country {
+                    name: String::from("Belgium"),
+                    competitions: vec![Competition {
+                        name: String::from("First Division A"),
+                        games: vec![
+                            game("Anderlecht", "Club Brugge", (2, 1), 1, GameStatus::Ended),
+                            game("Gent", "Genk", (0, 0), 2, GameStatus::Ended),
+                            game("Antwerp", "Anderlecht", (1, 1), 3, GameStatus::Upcoming),
+                        ],
+                    }],
+                },
+                Country {
+                    name: String::from("England"),
+                    competitions: vec![Competition {
+                        name: String::from("Premier League"),
+                        games: vec![game("Arsenal", "Chelsea", (5, 0), 3, GameStatus::Ended)],
+                    }],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn record_deduplicates() {
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        assert_eq!(store.record(&snapshot()).unwrap(), 3);
+        assert_eq!(store.record(&snapshot()).unwrap(), 0);
+        assert_eq!(store.results(&HistoryQuery::new()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn query_stored_results() {
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        store.record(&snapshot()).unwrap();
+
+        let anderlecht = store.results_for_team("anderlecht").unwrap();
+        assert_eq!(anderlecht.len(), 1);
+        assert_eq!(anderlecht[0].game.away_team, "Club Brugge");
+        assert_eq!(anderlecht[0].game.home_score, Some(2));
+
+        let belgium = store.results_for_competition("belgium first").unwrap();
+        assert_eq!(belgium.len(), 2);
+        // Both words have to be in the same team
+        let mixed = store.results_for_team("anderlecht brugge").unwrap();
+        assert!(mixed.is_empty());
+
+        let march_2 = store
+            .results_between(
+                Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap(),
+            )
+            .unwrap();
+        let teams: Vec<_> = march_2.iter().map(|p| p.game.home_team.as_str()).collect();
+        assert_eq!(teams, vec!["Gent", "Arsenal"]);
+
+        let combined = store
+            .results(
+                &HistoryQuery::new()
+                    .team("gen")
+                    .from(Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap()),
+            )
+            .unwrap();
+        assert_eq!(combined.len(), 1);
+        assert_eq!(combined[0].id(), snapshot().finished_games()[1].id());
+    }
+}
@@ -1,9 +1,22 @@
+mod clock;
+mod export;
 mod generic_structs;
 mod livescore;
+mod render;
 mod search;
 
+pub mod alerts;
+pub mod commands;
+pub mod contest;
+pub mod history;
+pub mod predict;
 pub mod ranking;
+pub mod ratings;
+pub mod standings;
+pub mod teams;
 
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use export::{EntryRow, GameRow};
 pub use generic_structs::*;
 
 pub async fn get_all_games() -> Result<Football, Box<dyn std::error::Error>> {
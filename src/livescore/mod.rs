@@ -197,45 +197,37 @@ impl std::cmp::PartialOrd for LiveScoreStage {
 /// Our order is by a priority of importance
 impl std::cmp::Ord for LiveScoreStage {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let a_priority = COUNTRY_PRIORITIES
-            .iter()
-            .position(|(country, _)| *country == self.country_name)
-            .unwrap_or(100);
-        let b_priority = COUNTRY_PRIORITIES
-            .iter()
-            .position(|(country, _)| *country == other.country_name)
-            .unwrap_or(100);
-        match a_priority.cmp(&b_priority) {
-            std::cmp::Ordering::Equal => {
-                // TODO This incorrectly gives higher priority to Ethiopia because its league is
-                // called "Premier League" too. It should just be considered no priority and kept
-                // in the alphabetical country order. If I stay explicit in the priority listing it
-                // might not matter anyway though.
-                let a_priority = COUNTRY_PRIORITIES
-                    .iter()
-                    .position(|(_, competition)| *competition == self.competition_name)
-                    .unwrap_or(100);
-                let b_priority = COUNTRY_PRIORITIES
-                    .iter()
-                    .position(|(_, competition)| *competition == other.competition_name)
-                    .unwrap_or(100);
-                match a_priority.cmp(&b_priority) {
-                    std::cmp::Ordering::Equal => {
-                        // If it was found Equal because same country/competition, then comparing
-                        // will do nothing (as you want). If it was found Equal because we do not
-                        // have it in our list of priorities and the default value was used, then
-                        // this comparison will sort things alphabetically.
-                        self.country_name
-                            .cmp(&other.country_name)
-                            .then(self.competition_name.cmp(&other.competition_name))
-                    }
-                    res => res,
-                }
-            }
-            res => res,
-        }
+        // If it was found Equal because same country/competition, then comparing the names will
+        // do nothing (as you want). If it was found Equal because we do not have it in our list of
+        // priorities and the default value was used, then this comparison will sort things
+        // alphabetically.
+        competition_priority(&self.country_name, &self.competition_name)
+            .cmp(&competition_priority(
+                &other.country_name,
+                &other.competition_name,
+            ))
+            .then(self.country_name.cmp(&other.country_name))
+            .then(self.competition_name.cmp(&other.competition_name))
     }
 }
+
+/// Position of the country and of the competition in COUNTRY_PRIORITIES, lower is more important.
+/// Anything not in the list gets 100.
+///
+/// TODO This incorrectly gives higher priority to Ethiopia because its league is called "Premier
+/// League" too. It should just be considered no priority and kept in the alphabetical country
+/// order. If I stay explicit in the priority listing it might not matter anyway though.
+pub(crate) fn competition_priority(country_name: &str, competition_name: &str) -> (usize, usize) {
+    let country_priority = COUNTRY_PRIORITIES
+        .iter()
+        .position(|(country, _)| *country == country_name)
+        .unwrap_or(100);
+    let competition_priority = COUNTRY_PRIORITIES
+        .iter()
+        .position(|(_, competition)| *competition == competition_name)
+        .unwrap_or(100);
+    (country_priority, competition_priority)
+}
 #[derive(Serialize, Deserialize, Debug)]
 struct LiveScoreGames {
     #[serde(rename = "Eps", default)]
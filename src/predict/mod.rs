@@ -0,0 +1,138 @@
+//! Guessing how games will go, for pre-match posts.
+
+mod poisson;
+mod simulation;
+
+pub use poisson::{PoissonConfig, PoissonModel, TeamStrength};
+pub use simulation::{SeasonOdds, Simulation, TeamOdds, Zone};
+
+/// Anything that can tell how likely every scoreline of a game is
+pub trait MatchModel {
+    fn score_matrix(&self, home_team: &str, away_team: &str) -> ScoreMatrix;
+}
+
+impl MatchModel for PoissonModel {
+    fn score_matrix(&self, home_team: &str, away_team: &str) -> ScoreMatrix {
+        self.predict(home_team, away_team)
+    }
+}
+
+/// Probability of every scoreline up to some number of goals per team. Whatever is left over
+/// beyond that is small enough to ignore.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreMatrix {
+    /// probabilities[home goals][away goals]
+    probabilities: Vec<Vec<f64>>,
+}
+
+impl ScoreMatrix {
+    /// Home and away goals as independent Poisson variables with the given means
+    pub fn from_rates(home_rate: f64, away_rate: f64, max_goals: u8) -> Self {
+        let home = poisson_pmf(home_rate, max_goals);
+        let away = poisson_pmf(away_rate, max_goals);
+        Self {
+            probabilities: home
+                .iter()
+                .map(|h| away.iter().map(|a| h * a).collect())
+                .collect(),
+        }
+    }
+
+    pub fn max_goals(&self) -> u8 {
+        (self.probabilities.len() - 1) as u8
+    }
+
+    pub fn probability(&self, home_goals: u8, away_goals: u8) -> f64 {
+        self.probabilities
+            .get(usize::from(home_goals))
+            .and_then(|row| row.get(usize::from(away_goals)))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// (home win, draw, away win)
+    pub fn outcome_probabilities(&self) -> (f64, f64, f64) {
+        let (mut home, mut draw, mut away) = (0.0, 0.0, 0.0);
+        for (h, row) in self.probabilities.iter().enumerate() {
+            for (a, p) in row.iter().enumerate() {
+                match h.cmp(&a) {
+                    std::cmp::Ordering::Greater => home += p,
+                    std::cmp::Ordering::Equal => draw += p,
+                    std::cmp::Ordering::Less => away += p,
+                }
+            }
+        }
+        (home, draw, away)
+    }
+
+    /// The `n` most likely scorelines, most likely first
+    pub fn most_likely(&self, n: usize) -> Vec<(u8, u8, f64)> {
+        let mut scores: Vec<_> = self
+            .probabilities
+            .iter()
+            .enumerate()
+            .flat_map(|(h, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(move |(a, p)| (h as u8, a as u8, *p))
+            })
+            .collect();
+        scores.sort_by(|x, y| y.2.total_cmp(&x.2).then((x.0, x.1).cmp(&(y.0, y.1))));
+        scores.truncate(n);
+        scores
+    }
+
+    /// One line for chat, e.g., "Genk 49% - draw 26% - Gent 25%, most likely 1-0 (12%)"
+    pub fn summary(&self, home_team: &str, away_team: &str) -> String {
+        let (home, draw, away) = self.outcome_probabilities();
+        let mut summary = format!(
+            "{} {:.0}% - draw {:.0}% - {} {:.0}%",
+            home_team,
+            home * 100.0,
+            draw * 100.0,
+            away_team,
+            away * 100.0
+        );
+        if let Some((h, a, p)) = self.most_likely(1).first() {
+            summary.push_str(&format!(", most likely {}-{} ({:.0}%)", h, a, p * 100.0));
+        }
+        summary
+    }
+}
+
+/// P(X = k) for k in 0..=max
+fn poisson_pmf(rate: f64, max: u8) -> Vec<f64> {
+    let mut result = Vec::with_capacity(usize::from(max) + 1);
+    let mut p = (-rate).exp();
+    for k in 0..=max {
+        result.push(p);
+        p *= rate / f64::from(k + 1);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_matrix() {
+        let matrix = ScoreMatrix::from_rates(1.5, 1.0, 10);
+        assert_eq!(matrix.max_goals(), 10);
+        let (home, draw, away) = matrix.outcome_probabilities();
+        assert!((home + draw + away - 1.0).abs() < 1e-6);
+        assert!(home > away);
+        assert!((matrix.probability(0, 0) - (-2.5f64).exp()).abs() < 1e-12);
+        assert_eq!(matrix.probability(11, 0), 0.0);
+        let top: Vec<_> = matrix
+            .most_likely(2)
+            .iter()
+            .map(|(h, a, _)| (*h, *a))
+            .collect();
+        assert_eq!(top, vec![(1, 0), (1, 1)]);
+        assert_eq!(
+            matrix.summary("Genk", "Gent"),
+            "Genk 49% - draw 26% - Gent 25%, most likely 1-0 (12%)"
+        );
+    }
+}
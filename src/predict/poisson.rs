@@ -0,0 +1,290 @@
+//! Goals as Poisson variables: the home team scores on average
+//! `home_advantage * attack(home) * defence(away)`, the away team `attack(away) * defence(home)`.
+//! Strengths are fitted on past results (Maher's model), recent games weighing more.
+
+use super::ScoreMatrix;
+use crate::generic_structs::Game;
+use crate::history::PlayedGame;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoissonConfig {
+    /// A game this many days before the fit counts half
+    pub half_life_days: f64,
+    /// Every team gets this many average games on top of its own, so a couple of results do not
+    /// make it the best team in the world
+    pub prior_weight: f64,
+    /// Goals per team in the score matrix
+    pub max_goals: u8,
+    pub iterations: usize,
+}
+
+impl Default for PoissonConfig {
+    fn default() -> Self {
+        Self {
+            half_life_days: 180.0,
+            prior_weight: 1.0,
+            max_goals: 10,
+            iterations: 100,
+        }
+    }
+}
+
+/// Both 1.0 for an average team
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TeamStrength {
+    /// Higher scores more
+    pub attack: f64,
+    /// Higher concedes more
+    pub defence: f64,
+}
+
+impl Default for TeamStrength {
+    fn default() -> Self {
+        Self {
+            attack: 1.0,
+            defence: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PoissonModel {
+    pub config: PoissonConfig,
+    strengths: BTreeMap<String, TeamStrength>,
+    /// Multiplier on the home team's goals
+    home_advantage: f64,
+    /// Average goals of an average team away from home
+    base_rate: f64,
+}
+
+impl PoissonModel {
+    /// Fits strengths on the finished games, weighing them by how long before `as_of` they were
+    /// played. Games after `as_of` are left out, so a fit over a fixed set of games with a fixed
+    /// `as_of` always gives the same model.
+    pub fn fit(games: &[PlayedGame], as_of: DateTime<Utc>, config: PoissonConfig) -> Self {
+        let samples: Vec<_> = games
+            .iter()
+            .filter(|played| played.game.start_time <= as_of)
+            .filter_map(|played| {
+                let game = &played.game;
+                let days = (as_of - game.start_time).num_seconds() as f64 / 86400.0;
+                Some(Sample {
+                    home: &game.home_team,
+                    away: &game.away_team,
+                    home_goals: f64::from(game.home_score?),
+                    away_goals: f64::from(game.away_score?),
+                    weight: 0.5f64.powf(days / config.half_life_days),
+                })
+            })
+            .collect();
+
+        let total_weight: f64 = samples.iter().map(|s| s.weight).sum();
+        let (home_goals, away_goals) = samples.iter().fold((0.0, 0.0), |(h, a), s| {
+            (h + s.weight * s.home_goals, a + s.weight * s.away_goals)
+        });
+        let base_rate = if total_weight > 0.0 && away_goals > 0.0 {
+            away_goals / total_weight
+        } else {
+            1.0
+        };
+        let mut home_advantage = if away_goals > 0.0 {
+            home_goals / away_goals
+        } else {
+            1.0
+        };
+
+        // BTreeMaps rather than HashMaps, so everything is added up in the same order every time
+        let teams: BTreeSet<&str> = samples
+            .iter()
+            .flat_map(|s| [s.home.as_str(), s.away.as_str()])
+            .collect();
+        let mut strengths: BTreeMap<String, TeamStrength> = teams
+            .iter()
+            .map(|team| (team.to_string(), TeamStrength::default()))
+            .collect();
+        let prior = config.prior_weight * base_rate;
+        for _ in 0..config.iterations {
+            // Goals and expected goals (before the team's own strength) per team, starting from
+            // the prior of some average games
+            let mut attack: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+            let mut defence: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+            for team in &teams {
+                attack.insert(team, (prior, prior));
+                defence.insert(team, (prior, prior));
+            }
+            for s in &samples {
+                let home = strengths[s.home.as_str()];
+                let away = strengths[s.away.as_str()];
+                // Expected goals of both sides, against the current strength of the other team
+                let w = s.weight;
+                let home_xg = w * base_rate * home_advantage * away.defence;
+                let away_xg = w * base_rate * home.defence;
+                add(&mut attack, s.home, w * s.home_goals, home_xg);
+                add(&mut attack, s.away, w * s.away_goals, away_xg);
+                let home_xga = w * base_rate * away.attack;
+                let away_xga = w * base_rate * home_advantage * home.attack;
+                add(&mut defence, s.home, w * s.away_goals, home_xga);
+                add(&mut defence, s.away, w * s.home_goals, away_xga);
+            }
+            for (team, strength) in strengths.iter_mut() {
+                let (goals, expected) = attack[team.as_str()];
+                strength.attack = goals / expected;
+                let (goals, expected) = defence[team.as_str()];
+                strength.defence = goals / expected;
+            }
+            // Keep the average team at 1.0, the base rate carries the level of goals
+            let n = strengths.len().max(1) as f64;
+            let mean_attack = strengths.values().map(|s| s.attack).sum::<f64>() / n;
+            let mean_defence = strengths.values().map(|s| s.defence).sum::<f64>() / n;
+            for strength in strengths.values_mut() {
+                strength.attack /= mean_attack;
+                strength.defence /= mean_defence;
+            }
+
+            let expected_home: f64 = samples
+                .iter()
+                .map(|s| {
+                    let (home, away) = (strengths[s.home.as_str()], strengths[s.away.as_str()]);
+                    s.weight * base_rate * home.attack * away.defence
+                })
+                .sum();
+            if expected_home > 0.0 && home_goals > 0.0 {
+                home_advantage = home_goals / expected_home;
+            }
+        }
+
+        Self {
+            config,
+            strengths,
+            home_advantage,
+            base_rate,
+        }
+    }
+
+    /// Average strengths for teams that were not in the games
+    pub fn strength(&self, team: &str) -> TeamStrength {
+        self.strengths.get(team).copied().unwrap_or_default()
+    }
+
+    pub fn home_advantage(&self) -> f64 {
+        self.home_advantage
+    }
+
+    /// Expected goals of (home, away)
+    pub fn expected_goals(&self, home_team: &str, away_team: &str) -> (f64, f64) {
+        let home = self.strength(home_team);
+        let away = self.strength(away_team);
+        (
+            self.base_rate * self.home_advantage * home.attack * away.defence,
+            self.base_rate * away.attack * home.defence,
+        )
+    }
+
+    pub fn predict(&self, home_team: &str, away_team: &str) -> ScoreMatrix {
+        let (home, away) = self.expected_goals(home_team, away_team);
+        ScoreMatrix::from_rates(home, away, self.config.max_goals)
+    }
+
+    pub fn predict_game(&self, game: &Game) -> ScoreMatrix {
+        self.predict(&game.home_team, &game.away_team)
+    }
+}
+
+struct Sample<'a> {
+    home: &'a String,
+    away: &'a String,
+    home_goals: f64,
+    away_goals: f64,
+    weight: f64,
+}
+
+fn add(totals: &mut BTreeMap<&str, (f64, f64)>, team: &str, goals: f64, expected: f64) {
+    let total = totals.get_mut(team).expect("Every team has a total");
+    total.0 += goals;
+    total.1 += expected;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic_structs::*;
+    use chrono::prelude::*;
+
+    fn played(home: &str, away: &str, score: (u8, u8), day: u32) -> PlayedGame {
+        PlayedGame {
+            country: String::from("Belgium"),
+            competition: String::from("First Division A"),
+            game: Game {
+                home_team: home.to_owned(),
+                away_team: away.to_owned(),
+                home_score: Some(score.0),
+                away_score: Some(score.1),
+                start_time: Utc.with_ymd_and_hms(2024, 3, day, 19, 0, 0).unwrap(),
+                status: GameStatus::Ended,
+            },
+        }
+    }
+
+    fn games() -> Vec<PlayedGame> {
+        let teams = ["Union SG", "Genk", "Gent", "Eupen"];
+        let scores = [
+            [(0, 0), (2, 0), (3, 1), (4, 0)],
+            [(1, 1), (0, 0), (2, 1), (3, 1)],
+            [(0, 2), (1, 1), (0, 0), (2, 0)],
+            [(0, 3), (1, 2), (1, 1), (0, 0)],
+        ];
+        let mut games = vec![];
+        for (h, home) in teams.iter().enumerate() {
+            for (a, away) in teams.iter().enumerate() {
+                if h != a {
+                    games.push(played(home, away, scores[h][a], (h * 4 + a + 1) as u32));
+                }
+            }
+        }
+        games
+    }
+
+    #[test]
+    fn fitting_strengths() {
+        let as_of = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        let model = PoissonModel::fit(&games(), as_of, PoissonConfig::default());
+        let union = model.strength("Union SG");
+        let eupen = model.strength("Eupen");
+        assert!(union.attack > 1.0 && union.defence < 1.0);
+        assert!(eupen.attack < 1.0 && eupen.defence > 1.0);
+        assert!(model.home_advantage() > 1.0);
+        assert_eq!(model.strength("Anderlecht"), TeamStrength::default());
+
+        let (home, _, away) = model.predict("Union SG", "Eupen").outcome_probabilities();
+        assert!(home > 0.6 && away < 0.15);
+
+        // Same games, same moment, same model
+        let again = PoissonModel::fit(&games(), as_of, PoissonConfig::default());
+        assert_eq!(
+            again.expected_goals("Genk", "Gent"),
+            model.expected_goals("Genk", "Gent")
+        );
+    }
+
+    #[test]
+    fn recent_games_count_more() {
+        let mut games = vec![played("Genk", "Gent", (0, 4), 1)];
+        games.extend((0..3).map(|_| played("Genk", "Gent", (4, 0), 2)));
+        let config = PoissonConfig {
+            half_life_days: 0.1,
+            ..Default::default()
+        };
+        let late = Utc.with_ymd_and_hms(2024, 3, 2, 20, 0, 0).unwrap();
+        let model = PoissonModel::fit(&games, late, config);
+        let (genk, gent) = model.expected_goals("Genk", "Gent");
+        assert!(genk > gent);
+
+        // Before the later games were played only the 0-4 counts
+        let early = Utc.with_ymd_and_hms(2024, 3, 1, 20, 0, 0).unwrap();
+        let model = PoissonModel::fit(&games, early, config);
+        let (genk, gent) = model.expected_goals("Genk", "Gent");
+        assert!(genk < gent);
+    }
+}
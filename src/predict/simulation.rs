@@ -0,0 +1,352 @@
+//! Playing the rest of the season over and over to see where everyone ends up.
+
+use super::{MatchModel, ScoreMatrix};
+use crate::generic_structs::*;
+use crate::standings::{match_fixtures, MatchResult, Standings};
+use std::fmt;
+
+/// Part of the table that means something, e.g., relegation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zone {
+    pub name: String,
+    /// Ranks, both inclusive
+    pub from_rank: usize,
+    pub to_rank: usize,
+}
+
+impl Zone {
+    pub fn new(name: &str, from_rank: usize, to_rank: usize) -> Self {
+        Self {
+            name: name.to_owned(),
+            from_rank,
+            to_rank,
+        }
+    }
+
+    pub fn premier_league() -> Vec<Self> {
+        vec![
+            Self::new("title", 1, 1),
+            Self::new("Champions League", 1, 4),
+            Self::new("relegation", 18, 20),
+        ]
+    }
+
+    /// Regular season, before the play-off split
+    pub fn pro_league() -> Vec<Self> {
+        vec![
+            Self::new("Champions' play-offs", 1, 6),
+            Self::new("relegation play-offs", 13, 16),
+        ]
+    }
+
+    /// Title and the bottom 3 for a league of `teams` teams
+    pub fn for_competition(country: &str, competition: &str, teams: usize) -> Vec<Self> {
+        match (
+            country.to_lowercase().as_str(),
+            competition.to_lowercase().as_str(),
+        ) {
+            ("england", "premier league") => Self::premier_league(),
+            ("belgium", "first division a") => Self::pro_league(),
+            _ => vec![
+                Self::new("title", 1, 1),
+                Self::new("relegation", teams.saturating_sub(2).max(1), teams),
+            ],
+        }
+    }
+
+    fn contains(&self, rank: usize) -> bool {
+        self.from_rank <= rank && rank <= self.to_rank
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamOdds {
+    pub team: String,
+    /// positions[0] is the probability of finishing first, and so on
+    pub positions: Vec<f64>,
+    /// Probability of ending up in every zone, same order as the zones
+    pub zones: Vec<(String, f64)>,
+    pub average_points: f64,
+}
+
+impl fmt::Display for TeamOdds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:.1}pts", self.team, self.average_points)?;
+        for (zone, probability) in &self.zones {
+            write!(f, ", {} {:.1}%", zone, probability * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SeasonOdds {
+    pub iterations: usize,
+    /// Sorted on average points, best first
+    pub teams: Vec<TeamOdds>,
+    /// Fixtures with a team that is not in the table, left out of the simulation
+    pub unmatched: Vec<Game>,
+}
+
+impl SeasonOdds {
+    pub fn team(&self, team: &str) -> Option<&TeamOdds> {
+        self.teams.iter().find(|odds| odds.team == team)
+    }
+}
+
+/// Same seed, same iterations, same input: same odds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Simulation {
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self {
+            iterations: 10_000,
+            seed: 0,
+        }
+    }
+}
+
+impl Simulation {
+    pub fn new(iterations: usize, seed: u64) -> Self {
+        Self { iterations, seed }
+    }
+
+    /// Plays the fixtures on top of the table with scores drawn from the model. Finished and
+    /// cancelled fixtures are skipped, they should be in the table already. Fixture teams are
+    /// matched to the table by name (see [crate::teams]), but the model is asked about them by
+    /// their fixture name.
+    pub fn run(
+        &self,
+        standings: &Standings,
+        fixtures: &[Game],
+        model: &dyn MatchModel,
+        zones: &[Zone],
+    ) -> SeasonOdds {
+        let table_teams = standings.teams();
+        let (matched, unmatched) = match_fixtures(&table_teams, fixtures);
+        let games: Vec<_> = matched
+            .into_iter()
+            .map(|(home, away, game)| Fixture {
+                home: table_teams[home].clone(),
+                away: table_teams[away].clone(),
+                scores: cumulative(&model.score_matrix(&game.home_team, &game.away_team)),
+            })
+            .collect();
+
+        let teams = table_teams.len();
+        let mut positions = vec![vec![0usize; teams]; teams];
+        let mut points = vec![0i64; teams];
+        let mut rng = SplitMix64(self.seed);
+        for _ in 0..self.iterations {
+            let mut season = standings.clone();
+            for game in &games {
+                let (home_score, away_score) = game.sample(rng.next_f64());
+                season.add_result(MatchResult {
+                    home_team: game.home.clone(),
+                    away_team: game.away.clone(),
+                    home_score,
+                    away_score,
+                });
+            }
+            for (rank, record) in season.ranked().iter().enumerate() {
+                let idx = table_teams
+                    .iter()
+                    .position(|team| team == &record.team)
+                    .expect("Simulated teams come from the table");
+                positions[idx][rank] += 1;
+                points[idx] += i64::from(record.points);
+            }
+        }
+
+        let n = self.iterations.max(1) as f64;
+        let mut odds: Vec<_> = table_teams
+            .iter()
+            .enumerate()
+            .map(|(idx, team)| {
+                let positions: Vec<_> = positions[idx].iter().map(|&c| c as f64 / n).collect();
+                let zones = zones
+                    .iter()
+                    .map(|zone| {
+                        let probability = positions
+                            .iter()
+                            .enumerate()
+                            .filter(|(rank, _)| zone.contains(rank + 1))
+                            .map(|(_, p)| p)
+                            .sum();
+                        (zone.name.clone(), probability)
+                    })
+                    .collect();
+                TeamOdds {
+                    team: team.clone(),
+                    positions,
+                    zones,
+                    average_points: points[idx] as f64 / n,
+                }
+            })
+            .collect();
+        odds.sort_by(|a, b| {
+            b.average_points
+                .total_cmp(&a.average_points)
+                .then(a.team.cmp(&b.team))
+        });
+
+        SeasonOdds {
+            iterations: self.iterations,
+            teams: odds,
+            unmatched,
+        }
+    }
+}
+
+struct Fixture {
+    home: String,
+    away: String,
+    /// (cumulative probability, home goals, away goals)
+    scores: Vec<(f64, u8, u8)>,
+}
+
+impl Fixture {
+    /// Scoreline for a uniform draw in [0, 1)
+    fn sample(&self, draw: f64) -> (u8, u8) {
+        // What the matrix leaves out beyond max goals ends up on the last score
+        let total = self.scores.last().map_or(1.0, |(p, _, _)| *p);
+        let idx = self
+            .scores
+            .partition_point(|(p, _, _)| *p <= draw * total)
+            .min(self.scores.len().saturating_sub(1));
+        self.scores.get(idx).map_or((0, 0), |(_, h, a)| (*h, *a))
+    }
+}
+
+fn cumulative(matrix: &ScoreMatrix) -> Vec<(f64, u8, u8)> {
+    let mut total = 0.0;
+    let mut result = vec![];
+    for (h, row) in matrix.probabilities.iter().enumerate() {
+        for (a, p) in row.iter().enumerate() {
+            total += p;
+            result.push((total, h as u8, a as u8));
+        }
+    }
+    result
+}
+
+/// Small and fast, and plenty random for this
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::beebs::{Entry, League};
+    use chrono::prelude::*;
+
+    /// Home team is a bit better, otherwise all teams are the same
+    struct Even;
+
+    impl MatchModel for Even {
+        fn score_matrix(&self, _: &str, _: &str) -> ScoreMatrix {
+            ScoreMatrix::from_rates(1.4, 1.1, 8)
+        }
+    }
+
+    fn fixture(home: &str, away: &str) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: None,
+            away_score: None,
+            start_time: Utc.with_ymd_and_hms(2024, 5, 1, 19, 0, 0).unwrap(),
+            status: GameStatus::Upcoming,
+        }
+    }
+
+    fn league() -> League {
+        let entry = |rank: i8, team: &str, points: i8| Entry {
+            rank,
+            team: team.to_owned(),
+            win: 0,
+            draw: 0,
+            lose: 0,
+            gf: 30,
+            ga: 30,
+            points,
+        };
+        League {
+            name: String::from("First Division A"),
+            entries: vec![
+                entry(1, "Club Brugge KV", 40),
+                entry(2, "KRC Genk", 38),
+                entry(3, "KAA Gent", 30),
+                entry(4, "KAS Eupen", 10),
+            ],
+        }
+    }
+
+    #[test]
+    fn simulating_the_run_in() {
+        let standings = Standings::from_league(&league());
+        let fixtures = vec![
+            fixture("Club Brugge", "Genk"),
+            fixture("Gent", "Eupen"),
+            fixture("Genk", "Gent"),
+            fixture("Eupen", "Club Brugge"),
+            fixture("Real Madrid", "Genk"),
+        ];
+        let zones = vec![Zone::new("title", 1, 1), Zone::new("relegation", 4, 4)];
+        let simulation = Simulation::new(2000, 42);
+        let odds = simulation.run(&standings, &fixtures, &Even, &zones);
+
+        assert_eq!(odds.unmatched.len(), 1);
+        assert_eq!(odds.teams.len(), 4);
+        let brugge = odds.team("Club Brugge KV").unwrap();
+        let genk = odds.team("KRC Genk").unwrap();
+        let eupen = odds.team("KAS Eupen").unwrap();
+        assert!(brugge.zones[0].1 > genk.zones[0].1);
+        assert!(genk.zones[0].1 > 0.1);
+        // 20 points behind with two games to go
+        assert_eq!(eupen.zones[1].1, 1.0);
+        assert_eq!(eupen.positions[3], 1.0);
+        for team in &odds.teams {
+            assert!((team.positions.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+
+        // Same seed, same odds
+        let again = simulation.run(&standings, &fixtures, &Even, &zones);
+        assert_eq!(again.teams, odds.teams);
+        assert!(brugge.to_string().starts_with("Club Brugge KV "));
+    }
+
+    #[test]
+    fn sampling_scores() {
+        let fixture = Fixture {
+            home: String::from("A"),
+            away: String::from("B"),
+            scores: cumulative(&ScoreMatrix::from_rates(1.0, 1.0, 5)),
+        };
+        assert_eq!(fixture.sample(0.0), (0, 0));
+        assert_eq!(fixture.sample(0.999999999), (5, 5));
+        let mut rng = SplitMix64(7);
+        let draws: Vec<_> = (0..1000).map(|_| rng.next_f64()).collect();
+        assert!(draws.iter().all(|d| (0.0..1.0).contains(d)));
+        let mean = draws.iter().sum::<f64>() / 1000.0;
+        assert!((mean - 0.5).abs() < 0.05);
+    }
+}
@@ -5,7 +5,7 @@ mod table;
 
 pub use search::Search;
 use std::collections::HashMap;
-use table::League;
+pub use table::{Entry, League};
 
 #[derive(Debug)]
 pub struct Beebs {
@@ -1,9 +1,9 @@
 use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::Deserialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct League {
-    name: String,
+    pub(crate) name: String,
     pub entries: Vec<Entry>,
 }
 
@@ -13,16 +13,16 @@ impl std::fmt::Display for League {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Entry {
-    rank: i8,
-    team: String,
-    win: i8,
-    draw: i8,
-    lose: i8,
-    gf: i8,
-    ga: i8,
-    points: i8,
+    pub(crate) rank: i8,
+    pub(crate) team: String,
+    pub(crate) win: i8,
+    pub(crate) draw: i8,
+    pub(crate) lose: i8,
+    pub(crate) gf: i8,
+    pub(crate) ga: i8,
+    pub(crate) points: i8,
 }
 
 impl std::fmt::Display for Entry {
@@ -70,6 +70,10 @@ impl League {
         Some(result)
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Gets all ranked entries
     pub fn get_ranking(&self) -> &Vec<Entry> {
         &self.entries
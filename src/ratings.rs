@@ -0,0 +1,360 @@
+//! Elo ratings, to tell a big game from a mismatch and to spot upsets. Works the same for clubs
+//! and national teams, across competitions: a team is its name.
+
+use crate::generic_structs::*;
+use crate::history::PlayedGame;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EloConfig {
+    /// Rating of a team the first time it shows up
+    pub initial: f64,
+    /// How much a single game can move the ratings
+    pub k: f64,
+    /// Added to the home team's rating when computing the expected result
+    pub home_advantage: f64,
+    /// Bigger wins count for more, as in the World Football Elo Ratings
+    pub goal_margin: bool,
+}
+
+impl Default for EloConfig {
+    fn default() -> Self {
+        Self::clubs()
+    }
+}
+
+impl EloConfig {
+    pub fn clubs() -> Self {
+        Self {
+            initial: 1500.0,
+            k: 20.0,
+            home_advantage: 65.0,
+            goal_margin: true,
+        }
+    }
+
+    /// Fewer games per year, so every game counts for more
+    pub fn national_teams() -> Self {
+        Self {
+            initial: 1500.0,
+            k: 40.0,
+            home_advantage: 100.0,
+            goal_margin: true,
+        }
+    }
+}
+
+/// Multiplier on k for the goal difference, 1 for a one goal game
+pub fn goal_margin_multiplier(goal_difference: u8) -> f64 {
+    match goal_difference {
+        0 | 1 => 1.0,
+        2 => 1.5,
+        n => (11.0 + f64::from(n)) / 8.0,
+    }
+}
+
+/// Expected score (1 win, 0.5 draw, 0 loss) of a team rated `rating` against `opponent`
+pub fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+/// What one game did to the ratings
+#[derive(Debug, Clone, PartialEq)]
+pub struct EloUpdate {
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: u8,
+    pub away_score: u8,
+    /// Expected score of the home team going into the game, home advantage included
+    pub expected_home: f64,
+    /// Points the home team won, the away team lost the same
+    pub change: f64,
+}
+
+impl EloUpdate {
+    /// The winner was given less than `threshold` (e.g., 0.3) chance going in
+    pub fn is_upset(&self, threshold: f64) -> bool {
+        match self.home_score.cmp(&self.away_score) {
+            std::cmp::Ordering::Greater => self.expected_home < threshold,
+            std::cmp::Ordering::Less => 1.0 - self.expected_home < threshold,
+            std::cmp::Ordering::Equal => false,
+        }
+    }
+}
+
+/// A point in a team's rating history
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatingChange {
+    pub time: DateTime<Utc>,
+    pub opponent: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct Elo {
+    pub config: EloConfig,
+    ratings: HashMap<String, f64>,
+    history: HashMap<String, Vec<RatingChange>>,
+    /// Games already counted, so the same game in the next snapshot is skipped
+    seen: HashSet<GameId>,
+    /// Tells which games are played at a neutral ground, none are if not set
+    neutral_venue: Option<NeutralVenue>,
+}
+
+type NeutralVenue = Arc<dyn Fn(&PlayedGame) -> bool + Send + Sync>;
+
+impl fmt::Debug for Elo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Elo")
+            .field("config", &self.config)
+            .field("ratings", &self.ratings)
+            .field("history", &self.history)
+            .field("seen", &self.seen)
+            .field("neutral_venue", &self.neutral_venue.is_some())
+            .finish()
+    }
+}
+
+impl Elo {
+    pub fn new(config: EloConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Games for which `neutral_venue` says so get no home advantage in [Elo::add_game] and
+    /// everything using it, e.g., cup finals or the competitions listed in a config file
+    pub fn with_neutral_venues(
+        mut self,
+        neutral_venue: impl Fn(&PlayedGame) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.neutral_venue = Some(Arc::new(neutral_venue));
+        self
+    }
+
+    /// Ratings from scratch over past results, oldest game first
+    pub fn replay(config: EloConfig, games: &[PlayedGame]) -> Self {
+        let mut elo = Self::new(config);
+        elo.add_games(games);
+        elo
+    }
+
+    /// Applies the games that were not counted yet, in order of kickoff
+    pub fn add_games(&mut self, games: &[PlayedGame]) -> Vec<EloUpdate> {
+        let mut games: Vec<_> = games.iter().collect();
+        games.sort_by_key(|played| played.game.start_time);
+        games
+            .into_iter()
+            .filter_map(|played| self.add_game(played))
+            .collect()
+    }
+
+    /// Applies the games that finished in the snapshot since the last time
+    pub fn update_from(&mut self, football: &Football) -> Vec<EloUpdate> {
+        self.add_games(&football.finished_games())
+    }
+
+    /// None if the game was counted already or did not finish. See [Elo::with_neutral_venues]
+    /// for games without a home team.
+    pub fn add_game(&mut self, played: &PlayedGame) -> Option<EloUpdate> {
+        let game = &played.game;
+        if game.status != GameStatus::Ended {
+            return None;
+        }
+        let (home_score, away_score) = (game.home_score?, game.away_score?);
+        if !self.seen.insert(played.id()) {
+            return None;
+        }
+        let before = (self.rating(&game.home_team), self.rating(&game.away_team));
+        let neutral = self
+            .neutral_venue
+            .as_ref()
+            .is_some_and(|neutral| neutral(played));
+        let update = self.result(
+            &game.home_team,
+            &game.away_team,
+            home_score,
+            away_score,
+            neutral,
+        );
+        self.record(&game.home_team, &game.away_team, game.start_time, before.0);
+        self.record(&game.away_team, &game.home_team, game.start_time, before.1);
+        Some(update)
+    }
+
+    /// Applies a result without any bookkeeping of history or seen games. Neutral means no home
+    /// advantage, e.g., most games at a World Cup.
+    pub fn result(
+        &mut self,
+        home_team: &str,
+        away_team: &str,
+        home_score: u8,
+        away_score: u8,
+        neutral: bool,
+    ) -> EloUpdate {
+        let expected_home = if neutral {
+            expected_score(self.rating(home_team), self.rating(away_team))
+        } else {
+            self.expected(home_team, away_team)
+        };
+        let actual = match home_score.cmp(&away_score) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        };
+        let multiplier = if self.config.goal_margin {
+            goal_margin_multiplier(home_score.abs_diff(away_score))
+        } else {
+            1.0
+        };
+        let change = self.config.k * multiplier * (actual - expected_home);
+        *self.rating_mut(home_team) += change;
+        *self.rating_mut(away_team) -= change;
+        EloUpdate {
+            home_team: home_team.to_owned(),
+            away_team: away_team.to_owned(),
+            home_score,
+            away_score,
+            expected_home,
+            change,
+        }
+    }
+
+    pub fn rating(&self, team: &str) -> f64 {
+        self.ratings
+            .get(team)
+            .copied()
+            .unwrap_or(self.config.initial)
+    }
+
+    /// Expected score of the home team, home advantage included
+    pub fn expected(&self, home_team: &str, away_team: &str) -> f64 {
+        expected_score(
+            self.rating(home_team) + self.config.home_advantage,
+            self.rating(away_team),
+        )
+    }
+
+    /// Every rated team, best first
+    pub fn ranked(&self) -> Vec<(&str, f64)> {
+        let mut ranked: Vec<_> = self
+            .ratings
+            .iter()
+            .map(|(team, rating)| (team.as_str(), *rating))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        ranked
+    }
+
+    /// Oldest first. Empty for teams that did not play.
+    pub fn history(&self, team: &str) -> &[RatingChange] {
+        self.history
+            .get(team)
+            .map_or(&[], |history| history.as_slice())
+    }
+
+    fn rating_mut(&mut self, team: &str) -> &mut f64 {
+        let initial = self.config.initial;
+        self.ratings.entry(team.to_owned()).or_insert(initial)
+    }
+
+    fn record(&mut self, team: &str, opponent: &str, time: DateTime<Utc>, before: f64) {
+        let after = self.rating(team);
+        self.history
+            .entry(team.to_owned())
+            .or_default()
+            .push(RatingChange {
+                time,
+                opponent: opponent.to_owned(),
+                before,
+                after,
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn played(home: &str, away: &str, score: (u8, u8), day: u32) -> PlayedGame {
+        PlayedGame {
+            country: String::from("Belgium"),
+            competition: String::from("First Division A"),
+            game: Game {
+                home_team: home.to_owned(),
+                away_team: away.to_owned(),
+                home_score: Some(score.0),
+                away_score: Some(score.1),
+                start_time: Utc.with_ymd_and_hms(2024, 3, day, 19, 0, 0).unwrap(),
+                status: GameStatus::Ended,
+            },
+        }
+    }
+
+    #[test]
+    fn expectations() {
+        assert!((expected_score(1500.0, 1500.0) - 0.5).abs() < 1e-9);
+        assert!((expected_score(1900.0, 1500.0) - 10.0 / 11.0).abs() < 1e-9);
+        assert_eq!(goal_margin_multiplier(1), 1.0);
+        assert_eq!(goal_margin_multiplier(2), 1.5);
+        assert_eq!(goal_margin_multiplier(3), 1.75);
+    }
+
+    #[test]
+    fn replaying_and_updating() {
+        let config = EloConfig {
+            home_advantage: 0.0,
+            ..EloConfig::clubs()
+        };
+        let games = vec![
+            played("Genk", "Gent", (1, 1), 2),
+            played("Genk", "Eupen", (3, 0), 1),
+        ];
+        let mut elo = Elo::replay(config, &games);
+        // 3-0 first: +20 * 1.75 * 0.5
+        let history = elo.history("Genk");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].opponent, "Eupen");
+        assert!((history[0].after - 1517.5).abs() < 1e-9);
+        assert_eq!(history[1].before, history[0].after);
+        // Then drawing at home as the favourite costs a bit
+        assert!(elo.rating("Genk") < 1517.5);
+        assert!((elo.rating("Eupen") - 1482.5).abs() < 1e-9);
+        assert_eq!(elo.ranked()[0].0, "Genk");
+
+        // Same game again in a later snapshot does nothing
+        assert!(elo.add_game(&games[0]).is_none());
+
+        let update = elo.add_game(&played("Eupen", "Genk", (1, 0), 9)).unwrap();
+        assert!(update.expected_home < 0.5);
+        assert!(update.is_upset(0.5));
+        assert!(!update.is_upset(0.3));
+        assert!(update.change > 10.0);
+    }
+
+    #[test]
+    fn neutral_venues() {
+        let final_ = |mut played: PlayedGame| {
+            played.competition = String::from("Cup Final");
+            played
+        };
+        // Like a list read from a config file
+        let neutral: HashSet<String> = ["Cup Final", "Super Cup"].map(String::from).into();
+        let mut elo = Elo::new(EloConfig::clubs())
+            .with_neutral_venues(move |played| neutral.contains(&played.competition));
+        let update = elo.add_game(&played("Genk", "Gent", (1, 0), 2)).unwrap();
+        assert!(update.expected_home > 0.5);
+        let update = elo.add_game(&final_(played("Eupen", "Westerlo", (1, 0), 3)));
+        assert_eq!(update.unwrap().expected_home, 0.5);
+        // Copies share the list
+        let mut copy = elo.clone();
+        let update = copy.add_game(&final_(played("Mechelen", "Standard", (2, 0), 4)));
+        assert_eq!(update.unwrap().expected_home, 0.5);
+    }
+}
@@ -0,0 +1,239 @@
+//! Turning a Football into a handful of lines that fit in a chat message (IRC lines are limited to
+//! roughly 450 bytes).
+
+use crate::generic_structs::*;
+use crate::livescore::competition_priority;
+
+/// Separates competitions on one line
+const COMPETITION_SEPARATOR: &str = " | ";
+/// Separates games of the same competition on one line
+const GAME_SEPARATOR: &str = ", ";
+
+impl Football {
+    /// Packs all games into as few lines of at most `max_bytes` bytes as possible. If that takes
+    /// more than `max_lines` lines, the least important competitions (see COUNTRY_PRIORITIES in
+    /// livescore) are dropped and summarised at the end, e.g., "+37 more in 12 competitions".
+    ///
+    /// A single game that does not fit on a line on its own gets cut off.
+    pub fn render_lines(&self, max_bytes: usize, max_lines: usize) -> Vec<String> {
+        if max_lines == 0 {
+            return vec![];
+        }
+        let items = self.render_items();
+        let (lines, packed) = pack(&items, max_bytes, None, 0);
+        if lines.len() <= max_lines {
+            return lines;
+        }
+
+        // Does not fit, so make sure there is room left on the last line for the summary. Using
+        // the totals gives an upper bound on how long the summary can get.
+        let reserve =
+            summary(items.len(), count_competitions(&items)).len() + COMPETITION_SEPARATOR.len();
+        let (mut lines, packed) = if packed == items.len() {
+            pack(&items, max_bytes, Some(max_lines), reserve)
+        } else {
+            (lines, packed)
+        };
+        let rest = &items[packed..];
+        let summary = summary(rest.len(), count_competitions(rest));
+        match lines.last_mut() {
+            Some(line) if !line.is_empty() => {
+                line.push_str(COMPETITION_SEPARATOR);
+                line.push_str(&summary);
+            }
+            _ => lines.push(truncate(&summary, max_bytes).to_owned()),
+        }
+        lines
+    }
+
+    /// (competition label, rendered game) for every game, most important competitions first.
+    fn render_items(&self) -> Vec<(String, String)> {
+        let mut competitions: Vec<_> = self
+            .countries
+            .iter()
+            .flat_map(|country| {
+                country
+                    .competitions
+                    .iter()
+                    .map(move |competition| (country, competition))
+            })
+            .collect();
+        // Stable, so anything with the same priority stays in the order we got it in.
+        competitions.sort_by_key(|(country, competition)| {
+            competition_priority(&country.name, &competition.name)
+        });
+        competitions
+            .into_iter()
+            .flat_map(|(country, competition)| {
+                let label = format!("{} {}", country.name, competition.name);
+                competition
+                    .games
+                    .iter()
+                    .map(move |game| (label.clone(), game.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Greedily packs items in lines. If `max_lines` is given, stops once that is full and the last
+/// line keeps `reserve` bytes free. Returns the lines and how many items ended up in them.
+fn pack(
+    items: &[(String, String)],
+    max_bytes: usize,
+    max_lines: Option<usize>,
+    reserve: usize,
+) -> (Vec<String>, usize) {
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut line_label: Option<&str> = None;
+    for (idx, (label, game)) in items.iter().enumerate() {
+        let is_last_line = max_lines == Some(lines.len() + 1);
+        let budget = if is_last_line {
+            max_bytes.saturating_sub(reserve)
+        } else {
+            max_bytes
+        };
+        let addition = if line.is_empty() {
+            format!("{}: {}", label, game)
+        } else if line_label == Some(label) {
+            format!("{}{}", GAME_SEPARATOR, game)
+        } else {
+            format!("{}{}: {}", COMPETITION_SEPARATOR, label, game)
+        };
+        if line.len() + addition.len() <= budget {
+            line.push_str(&addition);
+            line_label = Some(label);
+            continue;
+        }
+        if !line.is_empty() {
+            if is_last_line {
+                lines.push(line);
+                return (lines, idx);
+            }
+            lines.push(std::mem::take(&mut line));
+        }
+        // Fresh line, so the competition needs to be mentioned again.
+        let is_last_line = max_lines == Some(lines.len() + 1);
+        let budget = if is_last_line {
+            max_bytes.saturating_sub(reserve)
+        } else {
+            max_bytes
+        };
+        let fresh = format!("{}: {}", label, game);
+        line.push_str(truncate(&fresh, budget));
+        line_label = Some(label);
+        if line.is_empty() {
+            // Not even room for a bit of this game, give up here.
+            return (lines, idx);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    (lines, items.len())
+}
+
+fn count_competitions(items: &[(String, String)]) -> usize {
+    let mut labels: Vec<_> = items.iter().map(|(label, _)| label).collect();
+    labels.dedup();
+    labels.len()
+}
+
+fn summary(games: usize, competitions: usize) -> String {
+    format!(
+        "+{} more in {} competition{}",
+        games,
+        competitions,
+        if competitions == 1 { "" } else { "s" }
+    )
+}
+
+/// Cuts off at the last char boundary within max_bytes
+fn truncate(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn ended(home: &str, away: &str) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: Some(1),
+            away_score: Some(0),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 15, 0, 0).unwrap(),
+            status: GameStatus::Ended,
+        }
+    }
+
+    fn football() -> Football {
+        Football {
+            countries: vec![
+                Country {
+                    name: String::from("Belgium"),
+                    competitions: vec![Competition {
+                        name: String::from("First Division A"),
+                        games: vec![ended("Anderlecht", "Genk"), ended("Gent", "Antwerp")],
+                    }],
+                },
+                Country {
+                    name: String::from("Ethiopia"),
+                    competitions: vec![Competition {
+                        name: String::from("Higher League"),
+                        games: vec![ended("A", "B"), ended("C", "D"), ended("E", "F")],
+                    }],
+                },
+                Country {
+                    name: String::from("England"),
+                    competitions: vec![Competition {
+                        name: String::from("Premier League"),
+                        games: vec![ended("Arsenal", "Spurs")],
+                    }],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn everything_on_one_line() {
+        let lines = football().render_lines(450, 3);
+        assert_eq!(
+            lines,
+            vec![
+                "England Premier League: (FT) Arsenal 1-0 Spurs | \
+                Belgium First Division A: (FT) Anderlecht 1-0 Genk, (FT) Gent 1-0 Antwerp | \
+                Ethiopia Higher League: (FT) A 1-0 B, (FT) C 1-0 D, (FT) E 1-0 F"
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_respect_budget() {
+        let lines = football().render_lines(60, 10);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(line.len() <= 60, "Too long: {}", line);
+        }
+        // Competition repeated when it spills over to the next line
+        assert!(lines[2].starts_with("Belgium First Division A: "));
+    }
+
+    #[test]
+    fn summarise_least_important() {
+        let lines = football().render_lines(100, 1);
+        assert_eq!(
+            lines,
+            vec!["England Premier League: (FT) Arsenal 1-0 Spurs | +5 more in 2 competitions"]
+        );
+    }
+}
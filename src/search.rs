@@ -1,3 +1,4 @@
+use crate::clock::{Clock, SystemClock};
 use crate::generic_structs::*;
 use chrono::prelude::*;
 
@@ -5,10 +6,7 @@ impl Football {
     /// Splits string into pieces, only keeps games for which every piece is matched by either
     /// country, competition, or teams
     pub fn query(&self, query: &str) -> Football {
-        let query: Vec<_> = query
-            .split(|c: char| !c.is_ascii_alphabetic())
-            .map(|word| word.to_lowercase())
-            .collect();
+        let query = query_words(query);
         let mut games = Football { countries: vec![] };
         for country in &self.countries {
             let mut filteredcompetitions = vec![];
@@ -17,12 +15,15 @@ impl Football {
                     .games
                     .iter()
                     .filter(|game| {
-                        query.iter().all(|word| {
-                            country.name.to_lowercase().contains(word)
-                                || competition.name.to_lowercase().contains(word)
-                                || game.home_team.to_lowercase().contains(word)
-                                || game.away_team.to_lowercase().contains(word)
-                        })
+                        matches_words(
+                            &query,
+                            &[
+                                &country.name,
+                                &competition.name,
+                                &game.home_team,
+                                &game.away_team,
+                            ],
+                        )
                     })
                     .cloned()
                     .collect();
@@ -78,10 +79,7 @@ impl Football {
     // Idea: split up and see if I can just add the match number for each to get something
     // meaningful? (Perhaps this is more something for the bitap library side)
     pub fn mixed_query(&self, query: &str) -> Vec<(f64, Country, Competition, Game)> {
-        let query: Vec<_> = query
-            .split(|c: char| !c.is_ascii_alphabetic())
-            .map(|word| word.to_lowercase())
-            .collect();
+        let query = query_words(query);
         let bitap = bitap::Bitap::new().distance(100_000).threshold(0.45);
         let mut result = vec![];
         for country in &self.countries {
@@ -164,7 +162,17 @@ impl Football {
     /// Provide the numbers of hours to include. E.g., 10 and 16 will give games that started up to
     /// 10 hrs earlier up to games that will start in the next 16 hrs.
     pub fn sliding_window(&self, hours_before: u8, hours_after: u8) -> Football {
-        let now = Utc::now();
+        self.sliding_window_with(&SystemClock, hours_before, hours_after)
+    }
+
+    /// [Football::sliding_window] around the time given by clock
+    pub fn sliding_window_with(
+        &self,
+        clock: &impl Clock,
+        hours_before: u8,
+        hours_after: u8,
+    ) -> Football {
+        let now = clock.now();
         self.generic_filter(|game| {
             game.start_time <= (now + chrono::Duration::hours(hours_after.into()))
                 && game.start_time >= (now - chrono::Duration::hours(hours_before.into()))
@@ -172,20 +180,38 @@ impl Football {
     }
 
     pub fn today(&self) -> Football {
-        let now = Utc::now();
-        self.generic_filter(|game| game.start_time.ordinal() == now.ordinal())
+        self.today_with(&SystemClock)
+    }
+
+    pub fn today_with(&self, clock: &impl Clock) -> Football {
+        self.on_day(clock.now().date_naive())
     }
 
     pub fn tomorrow(&self) -> Football {
-        let now = Utc::now();
-        // TODO: Will fail on 31 December.
-        self.generic_filter(|game| game.start_time.ordinal() == now.ordinal() + 1)
+        self.tomorrow_with(&SystemClock)
+    }
+
+    pub fn tomorrow_with(&self, clock: &impl Clock) -> Football {
+        match clock.now().date_naive().succ_opt() {
+            Some(day) => self.on_day(day),
+            None => Football::default(),
+        }
     }
 
     pub fn yesterday(&self) -> Football {
-        let now = Utc::now();
-        // TODO: Will fail on 1 January.
-        self.generic_filter(|game| game.start_time.ordinal() == now.ordinal() - 1)
+        self.yesterday_with(&SystemClock)
+    }
+
+    pub fn yesterday_with(&self, clock: &impl Clock) -> Football {
+        match clock.now().date_naive().pred_opt() {
+            Some(day) => self.on_day(day),
+            None => Football::default(),
+        }
+    }
+
+    /// Games starting on the given (UTC) day
+    fn on_day(&self, day: NaiveDate) -> Football {
+        self.generic_filter(|game| game.start_time.date_naive() == day)
     }
 
     // TODO: This is more status than time so this module is badly named
@@ -240,3 +266,80 @@ impl Football {
         }
     }
 }
+
+/// Splits a query into lowercase words the way [Football::query] does
+pub(crate) fn query_words(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// True if every word is found in at least one of the haystacks, ignoring case
+pub(crate) fn matches_words(words: &[String], haystacks: &[&str]) -> bool {
+    let haystacks: Vec<_> = haystacks.iter().map(|h| h.to_lowercase()).collect();
+    words
+        .iter()
+        .all(|word| haystacks.iter().any(|haystack| haystack.contains(word)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+
+    fn football(start_times: &[DateTime<Utc>]) -> Football {
+        let games = start_times
+            .iter()
+            .map(|start_time| Game {
+                home_team: String::from("Gent"),
+                away_team: String::from("Genk"),
+                home_score: None,
+                away_score: None,
+                start_time: *start_time,
+                status: GameStatus::Upcoming,
+            })
+            .collect();
+        Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games,
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn days_across_new_year() {
+        let football = football(&[
+            Utc.with_ymd_and_hms(2024, 12, 31, 20, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 15, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 18, 0, 0).unwrap(),
+        ]);
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap());
+        assert_eq!(football.today_with(&clock).number_of_games(), 1);
+        assert_eq!(football.tomorrow_with(&clock).number_of_games(), 2);
+        let clock = FixedClock(Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap());
+        assert_eq!(football.yesterday_with(&clock).number_of_games(), 1);
+    }
+
+    #[test]
+    fn sliding_window_around_clock() {
+        let football = football(&[
+            Utc.with_ymd_and_hms(2024, 3, 2, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 2, 15, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 3, 3, 15, 0, 0).unwrap(),
+        ]);
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 3, 2, 12, 0, 0).unwrap());
+        assert_eq!(
+            football.sliding_window_with(&clock, 2, 4).number_of_games(),
+            1
+        );
+        assert_eq!(
+            football.sliding_window_with(&clock, 4, 4).number_of_games(),
+            2
+        );
+    }
+}
@@ -0,0 +1,332 @@
+//! Things the results alone do not tell: points deductions, games awarded by the league, teams
+//! thrown out mid-season. Kept in a file next to the config, e.g.
+//!
+//! ```json
+//! [
+//!   {"type": "points", "team": "Everton", "delta": -8, "reason": "Breach of PSR"},
+//!   {"type": "awarded_result", "home_team": "Standard", "away_team": "Anderlecht",
+//!    "home_score": 0, "away_score": 3, "reason": "Game abandoned", "competition": "First Division A"},
+//!   {"type": "expelled", "team": "Mouscron", "reason": "License revoked"}
+//! ]
+//! ```
+
+use super::{add_to_entry, rerank_entries, to_i8, MatchResult, OutOfRange, Standings};
+use crate::ranking::beebs::League;
+use crate::search::{matches_words, query_words};
+use crate::teams::best_match;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AdjustmentKind {
+    /// Negative for a deduction
+    Points { team: String, delta: i32 },
+    /// Result decided off the pitch, replaces whatever was played
+    AwardedResult {
+        home_team: String,
+        away_team: String,
+        home_score: u8,
+        away_score: u8,
+    },
+    /// Team and all of its results are taken out of the table
+    Expelled { team: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Adjustment {
+    #[serde(flatten)]
+    pub kind: AdjustmentKind,
+    pub reason: String,
+    /// Only apply to tables of this competition. Applies everywhere when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub competition: Option<String>,
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            AdjustmentKind::Points { team, delta } => write!(f, "{} {:+} pts", team, delta)?,
+            AdjustmentKind::AwardedResult {
+                home_team,
+                away_team,
+                home_score,
+                away_score,
+            } => write!(
+                f,
+                "{} {}-{} {} awarded",
+                home_team, home_score, away_score, away_team
+            )?,
+            AdjustmentKind::Expelled { team } => write!(f, "{} expelled", team)?,
+        }
+        write!(f, " ({})", self.reason)
+    }
+}
+
+/// A table with adjustments applied, and what was done to it
+#[derive(Debug, Clone)]
+pub struct AdjustedLeague {
+    pub league: League,
+    /// One line per applied adjustment, e.g., "Everton -8 pts (Breach of PSR)"
+    pub notes: Vec<String>,
+    /// Adjustments whose teams are not in the table
+    pub unmatched: Vec<Adjustment>,
+}
+
+impl fmt::Display for AdjustedLeague {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.league.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        for note in &self.notes {
+            writeln!(f, "* {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Adjustments {
+    pub adjustments: Vec<Adjustment>,
+}
+
+impl Adjustments {
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(Self::from_json(&content)?)
+    }
+
+    /// The adjustments that apply to the competition. Matched like [crate::Football::query]
+    /// matches words, so "premier" is enough.
+    pub fn for_competition(&self, competition: &str) -> Self {
+        Self {
+            adjustments: self
+                .adjustments
+                .iter()
+                .filter(|adjustment| match &adjustment.competition {
+                    Some(wanted) => matches_words(&query_words(wanted), &[competition]),
+                    None => true,
+                })
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Applies everything to the computed table, in order. Team names are matched with
+    /// [crate::teams]. Returns the adjustments that could not be applied.
+    pub fn apply_to_standings(&self, standings: &mut Standings) -> Vec<Adjustment> {
+        let mut unmatched = vec![];
+        for adjustment in &self.adjustments {
+            let teams = standings.teams();
+            let find = |team: &str| best_match(team, &teams).cloned();
+            match &adjustment.kind {
+                AdjustmentKind::Points { team, delta } => match find(team) {
+                    Some(team) => standings.adjust_points(&team, *delta),
+                    None => unmatched.push(adjustment.clone()),
+                },
+                AdjustmentKind::AwardedResult {
+                    home_team,
+                    away_team,
+                    home_score,
+                    away_score,
+                } => match (find(home_team), find(away_team)) {
+                    (Some(home_team), Some(away_team)) => {
+                        standings.remove_result(&home_team, &away_team);
+                        standings.add_result(MatchResult {
+                            home_team,
+                            away_team,
+                            home_score: *home_score,
+                            away_score: *away_score,
+                        });
+                    }
+                    _ => unmatched.push(adjustment.clone()),
+                },
+                AdjustmentKind::Expelled { team } => match find(team) {
+                    Some(team) => standings.remove_team(&team),
+                    None => unmatched.push(adjustment.clone()),
+                },
+            }
+        }
+        unmatched
+    }
+
+    /// Computed table with the adjustments applied, ready to show
+    pub fn adjust_standings(&self, standings: &Standings) -> Result<AdjustedLeague, OutOfRange> {
+        let mut standings = standings.clone();
+        let unmatched = self.apply_to_standings(&mut standings);
+        Ok(AdjustedLeague {
+            league: standings.to_league()?,
+            notes: self.notes(&unmatched),
+            unmatched,
+        })
+    }
+
+    /// Applies everything to a scraped table. The scraped table does not say which games it
+    /// counted, so an awarded result is added on top of it and an expelled team's results stay in
+    /// the other teams' lines. Only list those for sources that leave them out.
+    ///
+    /// Teams level on points after adjusting are ordered by goal difference, goals scored, and
+    /// their order in the scraped table.
+    pub fn adjust_league(&self, league: &League) -> Result<AdjustedLeague, OutOfRange> {
+        let mut entries = league.entries.clone();
+        let mut unmatched = vec![];
+        for adjustment in &self.adjustments {
+            let teams: Vec<_> = entries.iter().map(|entry| entry.team.clone()).collect();
+            let find = |team: &str| {
+                let team = best_match(team, &teams)?;
+                entries.iter().position(|entry| &entry.team == team)
+            };
+            match &adjustment.kind {
+                AdjustmentKind::Points { team, delta } => match find(team) {
+                    Some(idx) => {
+                        let points = i32::from(entries[idx].points) + delta;
+                        entries[idx].points = to_i8(&entries[idx].team, points.into())?;
+                    }
+                    None => unmatched.push(adjustment.clone()),
+                },
+                AdjustmentKind::AwardedResult {
+                    home_team,
+                    away_team,
+                    home_score,
+                    away_score,
+                } => match (find(home_team), find(away_team)) {
+                    (Some(home), Some(away)) => {
+                        add_to_entry(&mut entries[home], *home_score, *away_score)?;
+                        add_to_entry(&mut entries[away], *away_score, *home_score)?;
+                    }
+                    _ => unmatched.push(adjustment.clone()),
+                },
+                AdjustmentKind::Expelled { team } => match find(team) {
+                    Some(idx) => {
+                        entries.remove(idx);
+                    }
+                    None => unmatched.push(adjustment.clone()),
+                },
+            }
+        }
+        rerank_entries(&mut entries)?;
+        Ok(AdjustedLeague {
+            league: League {
+                name: league.name.clone(),
+                entries,
+            },
+            notes: self.notes(&unmatched),
+            unmatched,
+        })
+    }
+
+    fn notes(&self, unmatched: &[Adjustment]) -> Vec<String> {
+        self.adjustments
+            .iter()
+            .filter(|adjustment| !unmatched.contains(adjustment))
+            .map(|adjustment| adjustment.to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::beebs::Entry;
+
+    const ADJUSTMENTS: &str = r#"[
+        {"type": "points", "team": "Everton", "delta": -8, "reason": "Breach of PSR"},
+        {"type": "awarded_result", "home_team": "Standard", "away_team": "Anderlecht",
+         "home_score": 0, "away_score": 3, "reason": "Game abandoned",
+         "competition": "First Division A"},
+        {"type": "expelled", "team": "Mouscron", "reason": "License revoked"}
+    ]"#;
+
+    fn result(home: &str, away: &str, home_score: u8, away_score: u8) -> MatchResult {
+        MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score,
+            away_score,
+        }
+    }
+
+    #[test]
+    fn computed_table() {
+        let adjustments = Adjustments::from_json(ADJUSTMENTS).unwrap();
+        assert_eq!(adjustments.adjustments.len(), 3);
+        assert_eq!(
+            adjustments
+                .for_competition("Premier League")
+                .adjustments
+                .len(),
+            2
+        );
+
+        let mut standings = Standings::new("First Division A");
+        standings.add_result(result("Standard", "Anderlecht", 2, 1));
+        standings.add_result(result("Mouscron", "Standard", 5, 0));
+        standings.add_result(result("Anderlecht", "Mouscron", 0, 1));
+        standings.add_result(result("Gent", "Standard", 1, 1));
+
+        let adjusted = adjustments
+            .for_competition("First Division A")
+            .adjust_standings(&standings)
+            .unwrap();
+        let lines: Vec<_> = adjusted.to_string().lines().map(String::from).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "1. Anderlecht 3pts 1-0-0 3-0",
+                "2. Gent 1pts 0-1-0 1-1",
+                "3. Standard 1pts 0-1-1 1-4",
+                "* Standard 0-3 Anderlecht awarded (Game abandoned)",
+                "* Mouscron expelled (License revoked)",
+            ]
+        );
+        assert_eq!(adjusted.unmatched.len(), 1);
+    }
+
+    #[test]
+    fn scraped_table() {
+        let entry = |rank: i8, team: &str, points: i8| Entry {
+            rank,
+            team: team.to_owned(),
+            win: 0,
+            draw: 0,
+            lose: 0,
+            gf: 20,
+            ga: 20,
+            points,
+        };
+        let league = League {
+            name: String::from("Premier League"),
+            entries: vec![
+                entry(1, "Everton", 40),
+                entry(2, "Burnley", 35),
+                entry(3, "Luton Town", 33),
+            ],
+        };
+        let adjustments = Adjustments::from_json(ADJUSTMENTS).unwrap();
+        let adjusted = adjustments
+            .for_competition("Premier League")
+            .adjust_league(&league)
+            .unwrap();
+        let teams: Vec<_> = adjusted
+            .league
+            .entries
+            .iter()
+            .map(|e| (e.rank, e.team.as_str(), e.points))
+            .collect();
+        assert_eq!(
+            teams,
+            vec![
+                (1, "Burnley", 35),
+                (2, "Luton Town", 33),
+                (3, "Everton", 32)
+            ]
+        );
+        assert_eq!(adjusted.notes, vec!["Everton -8 pts (Breach of PSR)"]);
+    }
+}
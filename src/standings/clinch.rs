@@ -0,0 +1,709 @@
+//! What is settled already: who clinched a zone, who can no longer reach it, and how many points
+//! everyone else still needs ("magic numbers").
+//!
+//! Every win/draw/loss combination of the remaining fixtures counts, rivals playing each other
+//! included, but games that cannot change a team's rank anymore are skipped. Ties on points
+//! count as going either way, unless tiebreakers that only look at wins and head-to-head points
+//! split them. Should that still be too much to go through, it falls back to bounds on points,
+//! which never claim too much but might take a while longer to see that something is settled.
+
+use super::{match_fixtures, Standings, TiebreakRule};
+use crate::generic_structs::*;
+use crate::predict::Zone;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Steps one search may take before falling back to bounds
+const SEARCH_BUDGET: usize = 200_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneOutlook {
+    pub zone: Zone,
+    /// Ends up in the zone whatever happens
+    pub clinched: bool,
+    /// Cannot end up in the zone anymore
+    pub eliminated: bool,
+    /// Points still needed to be sure of finishing at the zone's last rank or higher. None if
+    /// even winning every game is not enough.
+    pub points_to_reach: Option<u32>,
+    /// Points still needed to be sure of finishing above the zone. None if even winning every
+    /// game is not enough, or if the zone starts at the top.
+    pub points_to_avoid: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamOutlook {
+    pub team: String,
+    pub points: i32,
+    pub games_left: u32,
+    pub best_rank: usize,
+    pub worst_rank: usize,
+    pub zones: Vec<ZoneOutlook>,
+}
+
+impl TeamOutlook {
+    /// Sentences for the things worth saying, e.g., "Club Brugge need 4 more points to clinch
+    /// title"
+    pub fn notes(&self) -> Vec<String> {
+        let mut notes = vec![];
+        for outlook in &self.zones {
+            let zone = &outlook.zone.name;
+            if outlook.clinched {
+                notes.push(format!("{} are certain of {}", self.team, zone));
+            } else if outlook.eliminated {
+                notes.push(format!("{} can no longer end up in {}", self.team, zone));
+            } else if outlook.zone.from_rank == 1 {
+                if let Some(points) = outlook.points_to_reach {
+                    notes.push(format!(
+                        "{} need {} more points to clinch {}",
+                        self.team, points, zone
+                    ));
+                }
+            } else if let Some(points) = outlook.points_to_avoid {
+                notes.push(format!(
+                    "{} need {} more points to be safe from {}",
+                    self.team, points, zone
+                ));
+            }
+        }
+        notes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClinchReport {
+    /// Every outcome that matters was gone through. If not, some teams only got bounds.
+    pub exact: bool,
+    /// Same order as the table
+    pub teams: Vec<TeamOutlook>,
+    /// Fixtures with a team that is not in the table, left out
+    pub unmatched: Vec<Game>,
+}
+
+impl fmt::Display for ClinchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for note in self.teams.iter().flat_map(|team| team.notes()) {
+            writeln!(f, "{}", note)?;
+        }
+        Ok(())
+    }
+}
+
+impl ClinchReport {
+    /// Fixtures that ended or got cancelled are skipped, they should be in the table already.
+    /// Fixture teams are matched to the table by name (see [crate::teams]).
+    pub fn compute(standings: &Standings, fixtures: &[Game], zones: &[Zone]) -> Self {
+        let ranked = standings.ranked();
+        let teams: Vec<String> = ranked.iter().map(|record| record.team.clone()).collect();
+        let (matched, unmatched) = match_fixtures(&teams, fixtures);
+        let remaining: Vec<_> = matched
+            .iter()
+            .map(|(home, away, _)| (*home, *away))
+            .collect();
+
+        let table = Table {
+            points: ranked.iter().map(|record| record.points).collect(),
+            wins: ranked.iter().map(|record| record.win).collect(),
+            played: standings
+                .results()
+                .iter()
+                .filter_map(|r| {
+                    Some((
+                        find_exact(&teams, &r.home_team)?,
+                        find_exact(&teams, &r.away_team)?,
+                        r.home_score.cmp(&r.away_score),
+                    ))
+                })
+                .collect(),
+            rules: standings.tiebreakers.rules.clone(),
+        };
+        // Head-to-head only means something if the results behind the table are all there
+        let complete = ranked.iter().enumerate().all(|(idx, record)| {
+            let games = table
+                .played
+                .iter()
+                .filter(|(h, a, _)| *h == idx || *a == idx);
+            games.count() == record.played as usize
+        });
+        let games_left: Vec<u32> = (0..teams.len())
+            .map(|idx| {
+                remaining
+                    .iter()
+                    .filter(|(h, a)| *h == idx || *a == idx)
+                    .count() as u32
+            })
+            .collect();
+
+        let mut exact = true;
+        let ranges: Vec<_> = (0..teams.len())
+            .map(|team| {
+                table.search(team, &remaining, complete).unwrap_or_else(|| {
+                    exact = false;
+                    table.bounds(team, &games_left)
+                })
+            })
+            .collect();
+
+        let teams = teams
+            .iter()
+            .enumerate()
+            .map(|(idx, team)| {
+                let range = &ranges[idx];
+                let zones = zones
+                    .iter()
+                    .map(|zone| ZoneOutlook {
+                        zone: zone.clone(),
+                        clinched: range.worst <= zone.to_rank && range.best >= zone.from_rank,
+                        eliminated: range.best > zone.to_rank || range.worst < zone.from_rank,
+                        points_to_reach: range.points_for(|worst| worst <= zone.to_rank),
+                        points_to_avoid: (zone.from_rank > 1)
+                            .then(|| range.points_for(|worst| worst < zone.from_rank))
+                            .flatten(),
+                    })
+                    .collect();
+                TeamOutlook {
+                    team: team.clone(),
+                    points: table.points[idx],
+                    games_left: games_left[idx],
+                    best_rank: range.best,
+                    worst_rank: range.worst,
+                    zones,
+                }
+            })
+            .collect();
+
+        Self {
+            exact,
+            teams,
+            unmatched,
+        }
+    }
+
+    pub fn team(&self, team: &str) -> Option<&TeamOutlook> {
+        self.teams.iter().find(|outlook| outlook.team == team)
+    }
+}
+
+fn find_exact(teams: &[String], team: &str) -> Option<usize> {
+    teams.iter().position(|t| t == team)
+}
+
+/// What the table looks like before the remaining fixtures, by team index
+struct Table {
+    points: Vec<i32>,
+    wins: Vec<u32>,
+    /// (home, away, home score compared to away score)
+    played: Vec<(usize, usize, Ordering)>,
+    rules: Vec<TiebreakRule>,
+}
+
+/// Where a team can end up
+struct RankRange {
+    best: usize,
+    worst: usize,
+    /// worst_by_gain[x]: worst rank when getting exactly x more points, None if impossible
+    worst_by_gain: Vec<Option<usize>>,
+}
+
+impl RankRange {
+    /// Fewest points after which every way of getting at least that many satisfies `ok`
+    fn points_for(&self, ok: impl Fn(usize) -> bool) -> Option<u32> {
+        let mut needed = None;
+        for (gain, worst) in self.worst_by_gain.iter().enumerate().rev() {
+            match worst {
+                Some(worst) if !ok(*worst) => break,
+                Some(_) => needed = Some(gain as u32),
+                None => {}
+            }
+        }
+        needed
+    }
+}
+
+impl Table {
+    /// Best and worst rank of team, going through every outcome of the remaining fixtures that
+    /// could still make a difference. None if that takes more than [SEARCH_BUDGET] steps.
+    fn search(
+        &self,
+        team: usize,
+        remaining: &[(usize, usize)],
+        complete: bool,
+    ) -> Option<RankRange> {
+        let mut search = Search::new(self, team, remaining, complete);
+        if !search.visit(0) {
+            return None;
+        }
+        let best = search.best;
+        search.reset(true);
+        if !search.visit(0) {
+            return None;
+        }
+        Some(RankRange {
+            best,
+            worst: search.worst_by_gain.iter().flatten().copied().max()?,
+            worst_by_gain: search.worst_by_gain,
+        })
+    }
+
+    /// Whether other finishes above team: Some(true) if it does, Some(false) if it does not, None
+    /// if that comes down to goals
+    fn compare(
+        &self,
+        team: usize,
+        other: usize,
+        points: &[i32],
+        wins: &[u32],
+        games: &[(usize, usize, Ordering)],
+        complete: bool,
+    ) -> Option<bool> {
+        if points[other] != points[team] {
+            return Some(points[other] > points[team]);
+        }
+        // Teams still level with these two on everything so far
+        let mut tied: Vec<usize> = (0..points.len())
+            .filter(|idx| points[*idx] == points[team])
+            .collect();
+        for rule in &self.rules {
+            match rule {
+                TiebreakRule::Wins => {
+                    if wins[other] != wins[team] {
+                        return Some(wins[other] > wins[team]);
+                    }
+                    tied.retain(|idx| wins[*idx] == wins[team]);
+                }
+                TiebreakRule::HeadToHeadPoints if complete => {
+                    let mini_league = |idx: usize| -> u32 {
+                        games
+                            .iter()
+                            .filter(|(h, a, _)| tied.contains(h) && tied.contains(a))
+                            .map(|(h, a, outcome)| match outcome {
+                                Ordering::Equal if *h == idx || *a == idx => 1,
+                                Ordering::Greater if *h == idx => 3,
+                                Ordering::Less if *a == idx => 3,
+                                _ => 0,
+                            })
+                            .sum()
+                    };
+                    let (ours, theirs) = (mini_league(team), mini_league(other));
+                    if ours != theirs {
+                        return Some(theirs > ours);
+                    }
+                    // Anything after this could be reapplied to a smaller group, too much to
+                    // follow here
+                    return None;
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Team between winning all and losing all of its games, as if nobody else played each
+    /// other. Never claims too much, but often too little.
+    fn bounds(&self, team: usize, games_left: &[u32]) -> RankRange {
+        let n = self.points.len();
+        let max: Vec<i32> = (0..n)
+            .map(|idx| self.points[idx] + 3 * games_left[idx] as i32)
+            .collect();
+        let others = || (0..n).filter(move |other| *other != team);
+        let best = 1 + others().filter(|o| self.points[*o] > max[team]).count();
+        let worst_with =
+            |final_points: i32| 1 + others().filter(|o| max[*o] >= final_points).count();
+        let worst_by_gain = (0..=3 * games_left[team] as i32)
+            .map(|gain| Some(worst_with(self.points[team] + gain)))
+            .collect();
+        RankRange {
+            best,
+            worst: worst_with(self.points[team]),
+            worst_by_gain,
+        }
+    }
+}
+
+/// Branch and bound over the remaining fixtures, looking for either the best rank of one team
+/// or its worst rank for every number of points it could still get
+struct Search<'a> {
+    table: &'a Table,
+    team: usize,
+    /// The team's own fixtures come first, after those its final points are known
+    fixtures: Vec<(usize, usize)>,
+    complete: bool,
+    worst: bool,
+    points: Vec<i32>,
+    wins: Vec<u32>,
+    /// Fixtures left per team, further down from where the search is at
+    left: Vec<i32>,
+    outcomes: Vec<Ordering>,
+    steps: usize,
+    best: usize,
+    worst_by_gain: Vec<Option<usize>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(table: &'a Table, team: usize, remaining: &[(usize, usize)], complete: bool) -> Self {
+        let involves = |(home, away): &&(usize, usize)| *home == team || *away == team;
+        let mut fixtures: Vec<_> = remaining.iter().filter(involves).copied().collect();
+        let games = fixtures.len();
+        fixtures.extend(remaining.iter().filter(|f| !involves(f)).copied());
+        let mut search = Self {
+            table,
+            team,
+            fixtures,
+            complete,
+            worst: false,
+            points: vec![],
+            wins: vec![],
+            left: vec![],
+            outcomes: vec![],
+            steps: 0,
+            best: 0,
+            worst_by_gain: vec![None; 3 * games + 1],
+        };
+        search.reset(false);
+        search
+    }
+
+    fn reset(&mut self, worst: bool) {
+        self.worst = worst;
+        self.points = self.table.points.clone();
+        self.wins = self.table.wins.clone();
+        self.left = vec![0; self.points.len()];
+        for &(home, away) in &self.fixtures {
+            self.left[home] += 1;
+            self.left[away] += 1;
+        }
+        self.steps = 0;
+        self.best = self.points.len() + 1;
+    }
+
+    /// False if the search ran out of steps
+    fn visit(&mut self, idx: usize) -> bool {
+        self.steps += 1;
+        if self.steps > SEARCH_BUDGET {
+            return false;
+        }
+        if !self.promising() {
+            return true;
+        }
+        let Some(&(home, away)) = self.fixtures.get(idx) else {
+            self.evaluate();
+            return true;
+        };
+        for outcome in self.candidates(home, away) {
+            self.play(home, away, outcome, 1);
+            self.outcomes.push(outcome);
+            let finished = self.visit(idx + 1);
+            self.outcomes.pop();
+            self.play(home, away, outcome, -1);
+            if !finished {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn play(&mut self, home: usize, away: usize, outcome: Ordering, sign: i32) {
+        let (home_points, away_points) = match outcome {
+            Ordering::Greater => (3, 0),
+            Ordering::Equal => (1, 1),
+            Ordering::Less => (0, 3),
+        };
+        self.points[home] += sign * home_points;
+        self.points[away] += sign * away_points;
+        let wins = |points| i64::from(sign) * i64::from(points == 3);
+        self.wins[home] = (i64::from(self.wins[home]) + wins(home_points)) as u32;
+        self.wins[away] = (i64::from(self.wins[away]) + wins(away_points)) as u32;
+        self.left[home] -= sign;
+        self.left[away] -= sign;
+    }
+
+    /// Whether anything further down could beat what was found so far
+    fn promising(&self) -> bool {
+        let team = self.team;
+        let others = || (0..self.points.len()).filter(move |other| *other != team);
+        if !self.worst {
+            let most = self.points[team] + 3 * self.left[team];
+            let surely_above = others().filter(|o| self.points[*o] > most).count();
+            return surely_above + 1 < self.best;
+        }
+        let gain = self.points[team] - self.table.points[team];
+        let games = self.left[team];
+        // Any number of points from the games left, except losing only one of the maximum
+        (0..=3 * games)
+            .filter(|extra| games == 0 || *extra != 3 * games - 1)
+            .any(|extra| {
+                let final_points = self.points[team] + extra;
+                let could_be_above = others()
+                    .filter(|o| self.points[*o] + 3 * self.left[*o] >= final_points)
+                    .count();
+                self.worst_by_gain[(gain + extra) as usize]
+                    .is_none_or(|worst| could_be_above + 1 > worst)
+            })
+    }
+
+    /// Outcomes worth trying. Once the team's own games are done, a game between two teams that
+    /// are already sure to end up above or below it does not matter, and a game between such a
+    /// team and one that is not only has one outcome worth looking at.
+    fn candidates(&self, home: usize, away: usize) -> Vec<Ordering> {
+        use Ordering::*;
+        let team = self.team;
+        if home == team || away == team {
+            // The team winning is the likely best case, losing the likely worst
+            let wins = if home == team { Greater } else { Less };
+            return if self.worst == (home == team) {
+                vec![wins.reverse(), Equal, wins]
+            } else {
+                vec![wins, Equal, wins.reverse()]
+            };
+        }
+        let final_points = self.points[team];
+        let settled = |idx: usize| {
+            self.points[idx] > final_points || self.points[idx] + 3 * self.left[idx] < final_points
+        };
+        // Looking for the best rank, the undecided team should get nothing, for the worst
+        // everything
+        match (settled(home), settled(away)) {
+            (true, true) => vec![Equal],
+            (true, false) if self.worst => vec![Less],
+            (true, false) => vec![Greater],
+            (false, true) if self.worst => vec![Greater],
+            (false, true) => vec![Less],
+            (false, false) => vec![Greater, Equal, Less],
+        }
+    }
+
+    fn evaluate(&mut self) {
+        let games: Vec<_> = self
+            .table
+            .played
+            .iter()
+            .copied()
+            .chain(
+                self.fixtures
+                    .iter()
+                    .zip(&self.outcomes)
+                    .map(|(&(home, away), &outcome)| (home, away, outcome)),
+            )
+            .collect();
+        let (mut above, mut undecided) = (0, 0);
+        for other in (0..self.points.len()).filter(|other| *other != self.team) {
+            let compared = self.table.compare(
+                self.team,
+                other,
+                &self.points,
+                &self.wins,
+                &games,
+                self.complete,
+            );
+            match compared {
+                Some(true) => above += 1,
+                Some(false) => {}
+                None => undecided += 1,
+            }
+        }
+        if self.worst {
+            let gain = (self.points[self.team] - self.table.points[self.team]) as usize;
+            let slot = &mut self.worst_by_gain[gain];
+            let worst = above + undecided + 1;
+            *slot = Some(slot.map_or(worst, |w| w.max(worst)));
+        } else {
+            self.best = self.best.min(above + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::beebs::{Entry, League};
+    use crate::standings::{MatchResult, Tiebreakers};
+    use chrono::prelude::*;
+
+    fn fixture(home: &str, away: &str) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: None,
+            away_score: None,
+            start_time: Utc.with_ymd_and_hms(2024, 5, 1, 19, 0, 0).unwrap(),
+            status: GameStatus::Upcoming,
+        }
+    }
+
+    fn entry(rank: i8, team: &str, win: i8, draw: i8, lose: i8) -> Entry {
+        Entry {
+            rank,
+            team: team.to_owned(),
+            win,
+            draw,
+            lose,
+            gf: 10,
+            ga: 10,
+            points: 3 * win + draw,
+        }
+    }
+
+    #[test]
+    fn end_of_season() {
+        let league = League {
+            name: String::from("First Division A"),
+            entries: vec![
+                entry(1, "Club Brugge", 20, 5, 3),
+                entry(2, "Genk", 18, 7, 3),
+                entry(3, "Gent", 15, 5, 8),
+                entry(4, "Eupen", 3, 5, 20),
+            ],
+        };
+        let standings = Standings::from_league(&league);
+        let fixtures = vec![
+            fixture("Club Brugge", "Genk"),
+            fixture("Gent", "Eupen"),
+            fixture("Genk", "Gent"),
+            fixture("Eupen", "Club Brugge"),
+        ];
+        let zones = vec![Zone::new("title", 1, 1), Zone::new("relegation", 4, 4)];
+        let report = ClinchReport::compute(&standings, &fixtures, &zones);
+        assert!(report.exact);
+
+        let brugge = report.team("Club Brugge").unwrap();
+        assert_eq!((brugge.points, brugge.games_left), (65, 2));
+        assert_eq!((brugge.best_rank, brugge.worst_rank), (1, 2));
+        // Genk can get to 67, but not when drawing with Brugge
+        assert_eq!(brugge.zones[0].points_to_reach, Some(2));
+        let eupen = report.team("Eupen").unwrap();
+        assert!(eupen.zones[1].clinched);
+        assert!(eupen.zones[0].eliminated);
+        let gent = report.team("Gent").unwrap();
+        assert!(gent.zones[0].eliminated);
+        assert_eq!(gent.zones[1].points_to_avoid, Some(0));
+        assert!(gent.zones[1].eliminated);
+
+        let notes = report.to_string();
+        assert!(notes.contains("Club Brugge need 2 more points to clinch title\n"));
+        assert!(notes.contains("Eupen are certain of relegation\n"));
+    }
+
+    #[test]
+    fn head_to_head_decides() {
+        // Last matchday of a group of four, tiebreakers start with head-to-head
+        let mut standings = Standings::new("Group A").with_tiebreakers(Tiebreakers::uefa_group());
+        let result = |home: &str, away: &str, home_score, away_score| MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score,
+            away_score,
+        };
+        standings.add_result(result("Belgium", "Wales", 2, 0));
+        standings.add_result(result("Czechia", "Estonia", 1, 0));
+        standings.add_result(result("Belgium", "Czechia", 0, 1));
+        standings.add_result(result("Wales", "Estonia", 3, 0));
+        standings.add_result(result("Estonia", "Belgium", 0, 2));
+        standings.add_result(result("Czechia", "Wales", 0, 0));
+        // Czechia 7, Belgium 6, Wales 4, Estonia 0: nothing left but another round
+        let fixtures = vec![fixture("Wales", "Belgium"), fixture("Estonia", "Czechia")];
+        let zones = vec![Zone::new("top two", 1, 2)];
+        let report = ClinchReport::compute(&standings, &fixtures, &zones);
+        let belgium = report.team("Belgium").unwrap();
+        // Lost to Czechia, so level with them is not enough for first. Only Wales can still
+        // pass them.
+        assert_eq!(belgium.best_rank, 1);
+        assert_eq!(belgium.worst_rank, 3);
+        assert_eq!(belgium.zones[0].points_to_reach, Some(1));
+        let estonia = report.team("Estonia").unwrap();
+        assert!(estonia.zones[0].eliminated);
+    }
+
+    #[test]
+    fn nations_league_group() {
+        let content = include_str!("../ranking/beebs/nations_league.html");
+        let group = League::from(content)
+            .into_iter()
+            .find(|league| league.name() == "UEFA Nations League League A Group 2")
+            .unwrap();
+        // Only the table, no results, so ties stay open
+        let standings = Standings::from_league(&group).with_tiebreakers(Tiebreakers::uefa_group());
+        let fixtures = vec![
+            fixture("Belgium", "Italy"),
+            fixture("France", "Israel"),
+            fixture("Italy", "France"),
+            fixture("Israel", "Belgium"),
+        ];
+        let zones = vec![Zone::new("quarter-finals", 1, 2)];
+        let report = ClinchReport::compute(&standings, &fixtures, &zones);
+        let belgium = report.team("Belgium").unwrap();
+        assert_eq!((belgium.points, belgium.best_rank), (4, 2));
+        assert!(!belgium.zones[0].eliminated);
+        assert_eq!(belgium.zones[0].points_to_reach, None);
+        assert!(report.team("Israel").unwrap().zones[0].eliminated);
+        let italy = report.team("Italy").unwrap();
+        // One more point puts them out of reach of Belgium
+        assert_eq!(italy.zones[0].points_to_reach, Some(1));
+    }
+
+    #[test]
+    fn rivals_playing_each_other() {
+        let mut entries = vec![
+            entry(1, "Club Brugge", 20, 0, 8),
+            entry(2, "Genk", 18, 1, 9),
+            entry(3, "Gent", 18, 1, 9),
+        ];
+        let bottom = ["Eupen", "Kortrijk", "RWDM", "OH Leuven", "Charleroi"];
+        for (idx, team) in bottom.iter().enumerate() {
+            entries.push(entry(idx as i8 + 4, team, 6, 2, 20));
+        }
+        let standings = Standings::from_league(&League {
+            name: String::from("First Division A"),
+            entries,
+        });
+        let mut fixtures = vec![fixture("Genk", "Gent"), fixture("Gent", "Genk")];
+        for (idx, home) in bottom.iter().enumerate() {
+            for away in &bottom[idx + 1..] {
+                fixtures.push(fixture(home, away));
+            }
+        }
+        assert_eq!(fixtures.len(), 12);
+        let zones = vec![Zone::new("title", 1, 1), Zone::new("top two", 1, 2)];
+        let report = ClinchReport::compute(&standings, &fixtures, &zones);
+        assert!(report.exact);
+
+        // Genk and Gent can each get to 61, but there are only six points between them
+        let brugge = report.team("Club Brugge").unwrap();
+        assert_eq!((brugge.best_rank, brugge.worst_rank), (1, 2));
+        assert!(brugge.zones[1].clinched);
+        assert!(!brugge.zones[0].clinched);
+        let genk = report.team("Genk").unwrap();
+        assert_eq!((genk.best_rank, genk.worst_rank), (1, 3));
+        assert!(!genk.zones[0].eliminated);
+        // Four points and Gent can have at most two
+        assert_eq!(genk.zones[1].points_to_reach, Some(4));
+        assert!(report.team("Eupen").unwrap().zones[1].eliminated);
+    }
+
+    #[test]
+    fn bounds_with_many_fixtures() {
+        let league = League {
+            name: String::from("League"),
+            entries: (0..6)
+                .map(|idx| entry(idx + 1, &format!("Team {}", idx), 10 - idx, 0, idx))
+                .collect(),
+        };
+        let standings = Standings::from_league(&league);
+        let mut fixtures = vec![];
+        for home in 0..6 {
+            for away in 0..6 {
+                if home != away {
+                    fixtures.push(fixture(
+                        &format!("Team {}", home),
+                        &format!("Team {}", away),
+                    ));
+                }
+            }
+        }
+        let report = ClinchReport::compute(&standings, &fixtures, &[Zone::new("title", 1, 1)]);
+        assert!(!report.exact);
+        let leader = report.team("Team 0").unwrap();
+        assert_eq!((leader.best_rank, leader.worst_rank), (1, 6));
+        // Team 1 can get 27 + 30
+        assert_eq!(leader.zones[0].points_to_reach, Some(28));
+    }
+}
@@ -0,0 +1,349 @@
+//! Comparing teams across groups, e.g., the best third-placed teams at the Euros or the overall
+//! Nations League ranking.
+
+use super::{MatchResult, TeamRecord, TiebreakRule, Tiebreakers};
+use crate::ranking::beebs::League;
+use crate::teams::best_match;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossGroupRules {
+    /// Applied after points. Head-to-head rules make no sense here, teams did not play each
+    /// other.
+    pub tiebreakers: Tiebreakers,
+    /// When groups differ in size, results against the teams below the size of the smallest
+    /// group do not count
+    pub discard_bottom: bool,
+}
+
+impl CrossGroupRules {
+    fn new(rules: Vec<TiebreakRule>, discard_bottom: bool) -> Self {
+        Self {
+            tiebreakers: Tiebreakers {
+                rules,
+                reapply_head_to_head: false,
+            },
+            discard_bottom,
+        }
+    }
+
+    /// Best third-placed teams at the Euros. The last criterion is the qualifying ranking, we
+    /// do not know that one.
+    pub fn euro_third_places() -> Self {
+        Self::new(
+            vec![
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::Wins,
+                TiebreakRule::FairPlay,
+            ],
+            false,
+        )
+    }
+
+    /// Best third-placed teams at the World Cup since 2026. Last criterion is the FIFA ranking.
+    pub fn world_cup_third_places() -> Self {
+        Self::new(
+            vec![
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::FairPlay,
+            ],
+            false,
+        )
+    }
+
+    /// Ranking across the groups of a Nations League league
+    pub fn nations_league() -> Self {
+        Self::new(
+            vec![
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::AwayGoalsScored,
+                TiebreakRule::Wins,
+                TiebreakRule::AwayWins,
+                TiebreakRule::FairPlay,
+            ],
+            true,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossGroupEntry {
+    pub group: String,
+    /// What counts for the ranking, so without any discarded games
+    pub record: TeamRecord,
+    pub discarded: Vec<MatchResult>,
+    /// Rule that put the team above the next one, if they were level on points
+    pub tiebreak: Option<TiebreakRule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrossGroupRanking {
+    /// Where the compared teams finished in their group
+    pub position: usize,
+    /// Best first
+    pub entries: Vec<CrossGroupEntry>,
+    /// Groups that needed games discarded, but whose results were not given. Their team counts
+    /// with all of its games.
+    pub missing_results: Vec<String>,
+    /// Groups with results to discard that their table does not have, e.g., a wrong score.
+    /// Those results are left in.
+    pub mismatched_results: Vec<String>,
+}
+
+impl fmt::Display for CrossGroupRanking {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let record = &entry.record;
+            write!(
+                f,
+                "{}. {} {}pts {}-{}-{} {}-{} ({})",
+                idx + 1,
+                record.team,
+                record.points,
+                record.win,
+                record.draw,
+                record.lose,
+                record.gf,
+                record.ga,
+                entry.group
+            )?;
+            if !entry.discarded.is_empty() {
+                write!(f, " [{} game(s) discarded]", entry.discarded.len())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl CrossGroupRanking {
+    /// Ranks the team at `position` (1 is the group winner) of every group, e.g., from
+    /// [League::from]. Groups with fewer teams are left out. Results are only needed when games
+    /// get discarded or for away rules, and are matched to the group by team name (see
+    /// [crate::teams]).
+    pub fn compute(
+        groups: &[League],
+        results: &[MatchResult],
+        position: usize,
+        rules: &CrossGroupRules,
+    ) -> Self {
+        let smallest = groups.iter().map(|g| g.entries.len()).min().unwrap_or(0);
+        let mut entries = vec![];
+        let mut counted = vec![];
+        let mut missing_results = vec![];
+        let mut mismatched_results = vec![];
+        for group in groups {
+            let Some(entry) = group.entries.iter().find(|e| e.rank as usize == position) else {
+                continue;
+            };
+            let teams: Vec<_> = group.entries.iter().map(|e| e.team.clone()).collect();
+            let team = &entry.team;
+            let is =
+                |name: &str, wanted: &str| best_match(name, &teams).is_some_and(|t| t == wanted);
+            let games: Vec<_> = results
+                .iter()
+                .filter(|r| is(&r.home_team, team) || is(&r.away_team, team))
+                .filter(|r| {
+                    teams.iter().any(|t| is(&r.home_team, t))
+                        && teams.iter().any(|t| is(&r.away_team, t))
+                })
+                .cloned()
+                .collect();
+
+            let bottom: Vec<_> = if rules.discard_bottom {
+                group
+                    .entries
+                    .iter()
+                    .filter(|e| e.rank as usize > smallest && &e.team != team)
+                    .map(|e| e.team.clone())
+                    .collect()
+            } else {
+                vec![]
+            };
+            let mut record = TeamRecord::from(entry);
+            let mut discarded = vec![];
+            for result in &games {
+                let at_home = is(&result.home_team, team);
+                let opponent = if at_home {
+                    &result.away_team
+                } else {
+                    &result.home_team
+                };
+                if bottom.iter().any(|b| is(opponent, b)) {
+                    let without = if at_home {
+                        record.without(result.home_score, result.away_score)
+                    } else {
+                        record.without(result.away_score, result.home_score)
+                    };
+                    match without {
+                        Some(without) => {
+                            record = without;
+                            discarded.push(result.clone());
+                        }
+                        None if !mismatched_results.contains(&group.name) => {
+                            mismatched_results.push(group.name.clone());
+                        }
+                        None => {}
+                    }
+                } else {
+                    // Same name as the record, so the away rules find it
+                    let mut result = result.clone();
+                    if at_home {
+                        result.home_team = team.clone();
+                    } else {
+                        result.away_team = team.clone();
+                    }
+                    counted.push(result);
+                }
+            }
+            let mismatched = mismatched_results.contains(&group.name);
+            if !bottom.is_empty() && discarded.is_empty() && !mismatched {
+                missing_results.push(group.name.clone());
+            }
+            entries.push(CrossGroupEntry {
+                group: group.name.clone(),
+                record,
+                discarded,
+                tiebreak: None,
+            });
+        }
+
+        let records: Vec<_> = entries.iter().map(|e| e.record.clone()).collect();
+        let ranked = rules.tiebreakers.rank(&records, &counted, &HashMap::new());
+        let entries = ranked
+            .into_iter()
+            .map(|(record, tiebreak)| {
+                let entry = entries
+                    .iter()
+                    .find(|e| e.record.team == record.team)
+                    .expect("Ranked records come from the entries");
+                CrossGroupEntry {
+                    tiebreak,
+                    ..entry.clone()
+                }
+            })
+            .collect();
+
+        Self {
+            position,
+            entries,
+            missing_results,
+            mismatched_results,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::beebs::Entry;
+
+    fn result(home: &str, away: &str, home_score: u8, away_score: u8) -> MatchResult {
+        MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score,
+            away_score,
+        }
+    }
+
+    #[test]
+    fn nations_league_third_places() {
+        let content = include_str!("../ranking/beebs/nations_league.html");
+        let groups: Vec<_> = League::from(content)
+            .into_iter()
+            .filter(|league| league.name().contains("League B"))
+            .collect();
+        let ranking =
+            CrossGroupRanking::compute(&groups, &[], 3, &CrossGroupRules::nations_league());
+        let teams: Vec<_> = ranking
+            .entries
+            .iter()
+            .map(|e| e.record.team.as_str())
+            .collect();
+        assert_eq!(
+            teams,
+            vec!["Slovenia", "Albania", "Iceland", "Republic of Ireland"]
+        );
+        assert!(ranking.missing_results.is_empty());
+        assert_eq!(
+            ranking.to_string().lines().next(),
+            Some("1. Slovenia 7pts 2-1-1 5-4 (UEFA Nations League League B Group 3)")
+        );
+    }
+
+    #[test]
+    fn discarding_games_against_the_bottom_team() {
+        let entry = |rank: i8, team: &str, (win, draw, lose): (i8, i8, i8), (gf, ga)| Entry {
+            rank,
+            team: team.to_owned(),
+            win,
+            draw,
+            lose,
+            gf,
+            ga,
+            points: 3 * win + draw,
+        };
+        let four = League {
+            name: String::from("Group 1"),
+            entries: vec![
+                entry(1, "North Macedonia", (3, 1, 0), (8, 1)),
+                entry(2, "Armenia", (1, 1, 2), (6, 7)),
+                entry(3, "Latvia", (1, 1, 2), (3, 8)),
+                entry(4, "Faroe Islands", (0, 3, 1), (4, 5)),
+            ],
+        };
+        let three = League {
+            name: String::from("Group 2"),
+            entries: vec![
+                entry(1, "Gibraltar", (2, 0, 0), (4, 1)),
+                entry(2, "San Marino", (1, 0, 1), (1, 1)),
+                entry(3, "Liechtenstein", (0, 0, 2), (1, 4)),
+            ],
+        };
+        let results = vec![
+            result("North Macedonia", "Faroe Islands", 3, 0),
+            result("Faroe Islands", "North Macedonia", 1, 1),
+            result("Gibraltar", "Liechtenstein", 2, 1),
+        ];
+        let ranking = CrossGroupRanking::compute(
+            &[four.clone(), three.clone()],
+            &results,
+            1,
+            &CrossGroupRules::nations_league(),
+        );
+        // North Macedonia down to 2-0-0 4-0 and 6 points, level with Gibraltar
+        let macedonia = &ranking.entries[0];
+        assert_eq!(macedonia.record.team, "North Macedonia");
+        assert_eq!(macedonia.record.points, 6);
+        assert_eq!((macedonia.record.gf, macedonia.record.ga), (4, 0));
+        assert_eq!(macedonia.discarded.len(), 2);
+        assert_eq!(macedonia.tiebreak, Some(TiebreakRule::GoalDifference));
+        assert_eq!(ranking.entries[1].record.team, "Gibraltar");
+        assert!(ranking.mismatched_results.is_empty());
+
+        // More goals than the table has
+        let wrong = vec![result("North Macedonia", "Faroe Islands", 9, 0)];
+        let ranking = CrossGroupRanking::compute(
+            &[four.clone(), three.clone()],
+            &wrong,
+            1,
+            &CrossGroupRules::nations_league(),
+        );
+        assert_eq!(ranking.mismatched_results, vec!["Group 1"]);
+        assert!(ranking.missing_results.is_empty());
+        let macedonia = &ranking.entries[0];
+        assert!(macedonia.discarded.is_empty());
+        assert_eq!((macedonia.record.points, macedonia.record.gf), (10, 8));
+
+        // Without the results of the four team group
+        let ranking =
+            CrossGroupRanking::compute(&[four, three], &[], 1, &CrossGroupRules::nations_league());
+        assert_eq!(ranking.missing_results, vec!["Group 1"]);
+        assert_eq!(ranking.entries[0].record.points, 10);
+    }
+}
@@ -0,0 +1,571 @@
+//! Computing league tables from results, for when BBC is slow or does not cover a league.
+
+mod adjustments;
+mod clinch;
+mod cross_group;
+mod playoffs;
+mod projection;
+mod scenarios;
+mod tiebreak;
+
+pub use adjustments::{AdjustedLeague, Adjustment, AdjustmentKind, Adjustments};
+pub use clinch::{ClinchReport, TeamOutlook, ZoneOutlook};
+pub use cross_group::{CrossGroupEntry, CrossGroupRanking, CrossGroupRules};
+pub use playoffs::{Playoff, PlayoffFormat, PlayoffGroup, Seed};
+pub use projection::{ProjectedEntry, ProjectedTable};
+pub use scenarios::{FixtureOutcome, RankingCase, Scenario, Scenarios};
+pub use tiebreak::{TiebreakRule, Tiebreakers};
+
+use crate::generic_structs::*;
+use crate::history::PlayedGame;
+use crate::ranking::beebs::{Entry, League};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// One finished game, all that matters for a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: u8,
+    pub away_score: u8,
+}
+
+impl MatchResult {
+    /// None unless the game ended and has a score
+    pub fn from_game(game: &Game) -> Option<Self> {
+        if game.status != GameStatus::Ended {
+            return None;
+        }
+        Some(Self {
+            home_team: game.home_team.clone(),
+            away_team: game.away_team.clone(),
+            home_score: game.home_score?,
+            away_score: game.away_score?,
+        })
+    }
+}
+
+/// A number that does not fit in a [League] line, those only go up to 127
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub team: String,
+    pub value: i64,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is too large for the table of {}",
+            self.value, self.team
+        )
+    }
+}
+
+impl Error for OutOfRange {}
+
+/// A team's line in the table while it is being computed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TeamRecord {
+    pub team: String,
+    pub played: u32,
+    pub win: u32,
+    pub draw: u32,
+    pub lose: u32,
+    pub gf: u32,
+    pub ga: u32,
+    pub points: i32,
+}
+
+impl TeamRecord {
+    pub fn new(team: &str) -> Self {
+        Self {
+            team: team.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn gd(&self) -> i32 {
+        self.gf as i32 - self.ga as i32
+    }
+
+    fn add(&mut self, goals_for: u8, goals_against: u8) {
+        self.played += 1;
+        self.gf += u32::from(goals_for);
+        self.ga += u32::from(goals_against);
+        match goals_for.cmp(&goals_against) {
+            Ordering::Greater => {
+                self.win += 1;
+                self.points += 3;
+            }
+            Ordering::Equal => {
+                self.draw += 1;
+                self.points += 1;
+            }
+            Ordering::Less => self.lose += 1,
+        }
+    }
+
+    /// Undoes [TeamRecord::add]. None if the record has no such game to take off, e.g., when it
+    /// came from a table that does not match the results.
+    fn without(&self, goals_for: u8, goals_against: u8) -> Option<Self> {
+        let mut record = self.clone();
+        record.played = record.played.checked_sub(1)?;
+        record.gf = record.gf.checked_sub(u32::from(goals_for))?;
+        record.ga = record.ga.checked_sub(u32::from(goals_against))?;
+        match goals_for.cmp(&goals_against) {
+            Ordering::Greater => {
+                record.win = record.win.checked_sub(1)?;
+                record.points = record.points.checked_sub(3)?;
+            }
+            Ordering::Equal => {
+                record.draw = record.draw.checked_sub(1)?;
+                record.points = record.points.checked_sub(1)?;
+            }
+            Ordering::Less => record.lose = record.lose.checked_sub(1)?,
+        }
+        Some(record)
+    }
+
+    fn to_entry(&self, rank: usize) -> Result<Entry, OutOfRange> {
+        let to_i8 = |value: i64| to_i8(&self.team, value);
+        Ok(Entry {
+            rank: to_i8(rank as i64)?,
+            team: self.team.clone(),
+            win: to_i8(self.win.into())?,
+            draw: to_i8(self.draw.into())?,
+            lose: to_i8(self.lose.into())?,
+            gf: to_i8(self.gf.into())?,
+            ga: to_i8(self.ga.into())?,
+            points: to_i8(self.points.into())?,
+        })
+    }
+}
+
+impl From<&Entry> for TeamRecord {
+    fn from(entry: &Entry) -> Self {
+        let count = |value: i8| u32::try_from(value).unwrap_or(0);
+        Self {
+            team: entry.team.clone(),
+            played: count(entry.win) + count(entry.draw) + count(entry.lose),
+            win: count(entry.win),
+            draw: count(entry.draw),
+            lose: count(entry.lose),
+            gf: count(entry.gf),
+            ga: count(entry.ga),
+            points: entry.points.into(),
+        }
+    }
+}
+
+/// Adds up results into a table. Ranked on points, then the tiebreakers. Those are goal
+/// difference and goals scored unless set otherwise.
+#[derive(Debug, Clone)]
+pub struct Standings {
+    name: String,
+    records: Vec<TeamRecord>,
+    results: Vec<MatchResult>,
+    tiebreakers: Tiebreakers,
+    /// Team -> penalty points, fewer is better
+    fair_play: HashMap<String, i32>,
+}
+
+impl Standings {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            records: vec![],
+            results: vec![],
+            tiebreakers: Tiebreakers::default(),
+            fair_play: HashMap::new(),
+        }
+    }
+
+    pub fn with_tiebreakers(mut self, tiebreakers: Tiebreakers) -> Self {
+        self.tiebreakers = tiebreakers;
+        self
+    }
+
+    /// Fair play penalty points (e.g., 1 per yellow, 3 per red), only used by
+    /// [TiebreakRule::FairPlay]. Teams without any count as 0.
+    pub fn set_fair_play(&mut self, team: &str, penalty_points: i32) {
+        self.fair_play.insert(team.to_owned(), penalty_points);
+    }
+
+    /// Finished games of the competition
+    pub fn from_competition(competition: &Competition) -> Self {
+        let mut standings = Self::new(&competition.name);
+        for game in &competition.games {
+            standings.add_game(game);
+        }
+        standings
+    }
+
+    /// Finished games from history. Filter them to one competition (and season) first.
+    pub fn from_history(name: &str, games: &[PlayedGame]) -> Self {
+        let mut standings = Self::new(name);
+        for played in games {
+            standings.add_game(&played.game);
+        }
+        standings
+    }
+
+    /// Starts from a scraped table. There are no results to go with it, so head-to-head
+    /// tiebreakers only see games added afterwards.
+    pub fn from_league(league: &League) -> Self {
+        let mut standings = Self::new(&league.name);
+        for entry in &league.entries {
+            standings.records.push(TeamRecord::from(entry));
+        }
+        standings
+    }
+
+    /// Makes sure the team shows up in the table, even without games played
+    pub fn add_team(&mut self, team: &str) {
+        self.record_mut(team);
+    }
+
+    /// Adds the game if it ended and has a score. Returns whether it was added.
+    pub fn add_game(&mut self, game: &Game) -> bool {
+        match MatchResult::from_game(game) {
+            Some(result) => {
+                self.add_result(result);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add_result(&mut self, result: MatchResult) {
+        self.record_mut(&result.home_team)
+            .add(result.home_score, result.away_score);
+        self.record_mut(&result.away_team)
+            .add(result.away_score, result.home_score);
+        self.results.push(result);
+    }
+
+    /// Removes the (first) result of home against away. Returns it if there was one, and if the
+    /// teams' records had the game to take off. Otherwise nothing changes.
+    pub fn remove_result(&mut self, home_team: &str, away_team: &str) -> Option<MatchResult> {
+        let idx = self
+            .results
+            .iter()
+            .position(|r| r.home_team == home_team && r.away_team == away_team)?;
+        let result = &self.results[idx];
+        let record = |team: &str| self.records.iter().find(|record| record.team == team);
+        let home = record(&result.home_team)?.without(result.home_score, result.away_score)?;
+        let away = record(&result.away_team)?.without(result.away_score, result.home_score)?;
+        for updated in [home, away] {
+            let team = updated.team.clone();
+            *self.record_mut(&team) = updated;
+        }
+        Some(self.results.remove(idx))
+    }
+
+    /// Takes the team out of the table, along with all results against it
+    pub fn remove_team(&mut self, team: &str) {
+        let games: Vec<_> = self
+            .results
+            .iter()
+            .filter(|r| r.home_team == team || r.away_team == team)
+            .map(|r| (r.home_team.clone(), r.away_team.clone()))
+            .collect();
+        for (home_team, away_team) in games {
+            self.remove_result(&home_team, &away_team);
+        }
+        self.records.retain(|record| record.team != team);
+    }
+
+    /// Points deduction (negative) or bonus, on top of what the results give
+    pub fn adjust_points(&mut self, team: &str, delta: i32) {
+        self.record_mut(team).points += delta;
+    }
+
+    /// All teams in the table, in no particular order
+    pub fn teams(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .map(|record| record.team.clone())
+            .collect()
+    }
+
+    fn record_mut(&mut self, team: &str) -> &mut TeamRecord {
+        match self.records.iter().position(|record| record.team == team) {
+            Some(idx) => &mut self.records[idx],
+            None => {
+                self.records.push(TeamRecord::new(team));
+                self.records.last_mut().unwrap()
+            }
+        }
+    }
+
+    pub fn results(&self) -> &[MatchResult] {
+        &self.results
+    }
+
+    /// Records from first to last
+    pub fn ranked(&self) -> Vec<&TeamRecord> {
+        self.ranked_with_tiebreaks()
+            .into_iter()
+            .map(|(record, _)| record)
+            .collect()
+    }
+
+    /// Records from first to last. If a team is level on points with the team below it, also
+    /// gives the rule that put it above.
+    pub fn ranked_with_tiebreaks(&self) -> Vec<(&TeamRecord, Option<TiebreakRule>)> {
+        self.tiebreakers
+            .rank(&self.records, &self.results, &self.fair_play)
+    }
+
+    /// Human readable explanation of every tie, e.g., "Genk above Gent on goal difference"
+    pub fn tiebreak_notes(&self) -> Vec<String> {
+        let ranked = self.ranked_with_tiebreaks();
+        ranked
+            .windows(2)
+            .filter_map(|pair| {
+                let rule = pair[0].1?;
+                Some(format!(
+                    "{} above {} on {}",
+                    pair[0].0.team, pair[1].0.team, rule
+                ))
+            })
+            .collect()
+    }
+
+    /// Same shape as a table scraped from BBC. Fails for a season too long for its numbers.
+    pub fn to_league(&self) -> Result<League, OutOfRange> {
+        Ok(League {
+            name: self.name.clone(),
+            entries: self
+                .ranked()
+                .iter()
+                .enumerate()
+                .map(|(idx, record)| record.to_entry(idx + 1))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Pairs up the fixtures still to be played with the teams of a table, by name (see
+/// [crate::teams]). Returns (home, away, fixture) with indexes into teams, and the fixtures with a
+/// team that is not in the table. Finished and cancelled fixtures are left out.
+pub(crate) fn match_fixtures<'a>(
+    teams: &[String],
+    fixtures: &'a [Game],
+) -> (Vec<(usize, usize, &'a Game)>, Vec<Game>) {
+    let fixture_teams: Vec<_> = fixtures
+        .iter()
+        .flat_map(|game| [game.home_team.clone(), game.away_team.clone()])
+        .collect();
+    let pairs = crate::teams::match_names(&fixture_teams, teams);
+    let find = |team: &str| {
+        let (_, table_team) = pairs
+            .iter()
+            .find(|(fixture_team, _)| fixture_team == team)?;
+        teams.iter().position(|team| team == table_team)
+    };
+    let mut matched = vec![];
+    let mut unmatched = vec![];
+    for game in fixtures {
+        if matches!(game.status, GameStatus::Ended | GameStatus::Cancelled) {
+            continue;
+        }
+        match (find(&game.home_team), find(&game.away_team)) {
+            (Some(home), Some(away)) => matched.push((home, away, game)),
+            _ => unmatched.push(game.clone()),
+        }
+    }
+    (matched, unmatched)
+}
+
+/// Reorders entries on points, goal difference, and goals scored, keeping the current order for
+/// anything level on those. Ranks get renumbered.
+fn rerank_entries(entries: &mut [Entry]) -> Result<(), OutOfRange> {
+    entries.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then((b.gf - b.ga).cmp(&(a.gf - a.ga)))
+            .then(b.gf.cmp(&a.gf))
+            .then(a.rank.cmp(&b.rank))
+    });
+    for (idx, entry) in entries.iter_mut().enumerate() {
+        entry.rank = to_i8(&entry.team, idx as i64 + 1)?;
+    }
+    Ok(())
+}
+
+/// Adds one game to a scraped entry, the [TeamRecord::add] of [Entry]. Leaves the entry as it
+/// was if the result does not fit.
+fn add_to_entry(entry: &mut Entry, goals_for: u8, goals_against: u8) -> Result<(), OutOfRange> {
+    let (win, draw, lose, points) = match goals_for.cmp(&goals_against) {
+        Ordering::Greater => (1, 0, 0, 3),
+        Ordering::Equal => (0, 1, 0, 1),
+        Ordering::Less => (0, 0, 1, 0),
+    };
+    let add = |value: i8, extra: u8| to_i8(&entry.team, i64::from(value) + i64::from(extra));
+    *entry = Entry {
+        win: add(entry.win, win)?,
+        draw: add(entry.draw, draw)?,
+        lose: add(entry.lose, lose)?,
+        gf: add(entry.gf, goals_for)?,
+        ga: add(entry.ga, goals_against)?,
+        points: add(entry.points, points)?,
+        ..entry.clone()
+    };
+    Ok(())
+}
+
+/// Entry uses i8 everywhere
+fn to_i8(team: &str, value: i64) -> Result<i8, OutOfRange> {
+    i8::try_from(value).map_err(|_| OutOfRange {
+        team: team.to_owned(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn result(home: &str, away: &str, home_score: u8, away_score: u8) -> MatchResult {
+        MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score,
+            away_score,
+        }
+    }
+
+    #[test]
+    fn table_from_results() {
+        let mut standings = Standings::new("First Division A");
+        standings.add_result(result("Anderlecht", "Genk", 2, 0));
+        standings.add_result(result("Genk", "Club Brugge", 1, 1));
+        standings.add_result(result("Club Brugge", "Anderlecht", 3, 1));
+        standings.add_result(result("Gent", "Genk", 0, 0));
+        standings.add_result(result("Gent", "Antwerp", 1, 0));
+        standings.add_team("Antwerp");
+        standings.add_team("Westerlo");
+
+        let league = standings.to_league().unwrap();
+        let teams: Vec<_> = league.entries.iter().map(|e| e.team.as_str()).collect();
+        // Brugge and Gent level on points, Brugge better goal difference
+        assert_eq!(
+            teams,
+            vec![
+                "Club Brugge",
+                "Gent",
+                "Anderlecht",
+                "Genk",
+                "Westerlo",
+                "Antwerp"
+            ]
+        );
+        assert_eq!(
+            league.entries[0].to_string(),
+            "1. Club Brugge 4pts 1-1-0 4-2"
+        );
+        assert_eq!(league.entries[5].to_string(), "6. Antwerp 0pts 0-0-1 0-1");
+    }
+
+    #[test]
+    fn removing_results() {
+        let mut standings = Standings::new("First Division A");
+        standings.add_result(result("Anderlecht", "Genk", 2, 0));
+        standings.adjust_points("Anderlecht", -3);
+        assert!(standings.remove_result("Anderlecht", "Genk").is_some());
+        assert_eq!(standings.remove_result("Anderlecht", "Genk"), None);
+        let anderlecht = standings.ranked()[1];
+        assert_eq!((anderlecht.played, anderlecht.points), (0, -3));
+
+        // A scraped line without any wins has no win to take off
+        let entry = Entry {
+            rank: 1,
+            team: String::from("Genk"),
+            win: 0,
+            draw: 2,
+            lose: 0,
+            gf: 1,
+            ga: 1,
+            points: 2,
+        };
+        let record = TeamRecord::from(&entry);
+        assert_eq!(record.without(3, 0), None);
+        assert_eq!(
+            record.without(1, 1).map(|r| (r.played, r.points)),
+            Some((1, 1))
+        );
+    }
+
+    #[test]
+    fn adding_to_scraped_entries() {
+        let mut entry = Entry {
+            rank: 1,
+            team: String::from("Genk"),
+            win: 40,
+            draw: 0,
+            lose: 0,
+            gf: 100,
+            ga: 10,
+            points: 120,
+        };
+        add_to_entry(&mut entry, 3, 1).unwrap();
+        assert_eq!((entry.win, entry.gf, entry.points), (41, 103, 123));
+        // Nothing changes when one of the numbers does not fit
+        assert_eq!(add_to_entry(&mut entry, 200, 0).unwrap_err().value, 303);
+        add_to_entry(&mut entry, 1, 0).unwrap();
+        assert_eq!(add_to_entry(&mut entry, 1, 0).unwrap_err().value, 129);
+        assert_eq!(entry.to_string(), "1. Genk 126pts 42-0-0 104-11");
+    }
+
+    #[test]
+    fn only_finished_games() {
+        let kickoff = Utc.with_ymd_and_hms(2024, 3, 2, 19, 0, 0).unwrap();
+        let competition = Competition {
+            name: String::from("Cup"),
+            games: vec![
+                Game {
+                    home_team: String::from("A"),
+                    away_team: String::from("B"),
+                    home_score: Some(1),
+                    away_score: Some(0),
+                    start_time: kickoff,
+                    status: GameStatus::Ended,
+                },
+                Game {
+                    home_team: String::from("B"),
+                    away_team: String::from("A"),
+                    home_score: Some(5),
+                    away_score: Some(0),
+                    start_time: kickoff,
+                    status: GameStatus::Ongoing(String::from("60'")),
+                },
+            ],
+        };
+        let standings = Standings::from_competition(&competition);
+        assert_eq!(standings.results().len(), 1);
+        assert_eq!(standings.ranked()[0].team, "A");
+    }
+
+    #[test]
+    fn too_many_points_for_a_table() {
+        let mut standings = Standings::new("First Division A");
+        for _ in 0..42 {
+            standings.add_result(result("Genk", "Gent", 1, 0));
+        }
+        assert_eq!(standings.to_league().unwrap().entries[0].points, 126);
+        standings.add_result(result("Genk", "Gent", 1, 0));
+        assert_eq!(
+            standings.to_league().map(|league| league.entries.len()),
+            Err(OutOfRange {
+                team: String::from("Genk"),
+                value: 129
+            })
+        );
+    }
+}
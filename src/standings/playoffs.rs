@@ -0,0 +1,398 @@
+//! Leagues that split up after the regular season, like the Belgian one: the top 6 play for the
+//! title, 7 to 12 for a European ticket, and the bottom 4 against relegation. Everyone starts the
+//! play-offs with half of their points, rounded up.
+
+use super::{MatchResult, OutOfRange, Standings, TeamRecord};
+use crate::generic_structs::*;
+use crate::ranking::beebs::League;
+use crate::teams::best_match;
+
+/// Which part of the regular-season table goes into a play-off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayoffGroup {
+    pub name: String,
+    /// Regular-season ranks, both inclusive
+    pub from_rank: usize,
+    pub to_rank: usize,
+    pub halve_points: bool,
+}
+
+impl PlayoffGroup {
+    pub fn new(name: &str, from_rank: usize, to_rank: usize, halve_points: bool) -> Self {
+        Self {
+            name: name.to_owned(),
+            from_rank,
+            to_rank,
+            halve_points,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayoffFormat {
+    pub groups: Vec<PlayoffGroup>,
+}
+
+impl PlayoffFormat {
+    /// Belgian Pro League since 2023-24
+    pub fn pro_league() -> Self {
+        Self {
+            groups: vec![
+                PlayoffGroup::new("Champions' play-offs", 1, 6, true),
+                PlayoffGroup::new("Europe play-offs", 7, 12, true),
+                PlayoffGroup::new("Relegation play-offs", 13, 16, true),
+            ],
+        }
+    }
+
+    /// None for leagues that just play a regular season
+    pub fn for_competition(country: &str, competition: &str) -> Option<Self> {
+        match (
+            country.to_lowercase().as_str(),
+            competition.to_lowercase().as_str(),
+        ) {
+            ("belgium", "first division a") => Some(Self::pro_league()),
+            _ => None,
+        }
+    }
+
+    /// Starting tables of every play-off, from the final regular-season table. Groups reaching
+    /// past the end of the table get whoever is there.
+    pub fn start(&self, regular_season: &League) -> Vec<Playoff> {
+        self.groups
+            .iter()
+            .map(|group| Playoff::start(group, regular_season))
+            .collect()
+    }
+}
+
+/// How a team went into the play-off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seed {
+    pub team: String,
+    pub regular_rank: usize,
+    pub regular_points: i32,
+    /// What the team starts the play-off with
+    pub points: i32,
+    /// Got half a point for free when halving. Such a team loses out on ties.
+    pub rounded: bool,
+}
+
+/// One play-off, continued from its results. Ranked on points, then teams that were not rounded
+/// up go above the ones that were, then the regular-season rank decides.
+#[derive(Debug, Clone)]
+pub struct Playoff {
+    pub name: String,
+    seeds: Vec<Seed>,
+    standings: Standings,
+}
+
+impl Playoff {
+    fn start(group: &PlayoffGroup, regular_season: &League) -> Self {
+        let mut standings = Standings::new(&group.name);
+        let mut seeds = vec![];
+        for entry in &regular_season.entries {
+            let rank = entry.rank as usize;
+            if rank < group.from_rank || rank > group.to_rank {
+                continue;
+            }
+            let regular_points = i32::from(entry.points);
+            let (points, rounded) = if group.halve_points {
+                ((regular_points + 1).div_euclid(2), regular_points % 2 != 0)
+            } else {
+                (regular_points, false)
+            };
+            standings.add_team(&entry.team);
+            standings.adjust_points(&entry.team, points);
+            seeds.push(Seed {
+                team: entry.team.clone(),
+                regular_rank: rank,
+                regular_points,
+                points,
+                rounded,
+            });
+        }
+        Self {
+            name: group.name.clone(),
+            seeds,
+            standings,
+        }
+    }
+
+    pub fn seeds(&self) -> &[Seed] {
+        &self.seeds
+    }
+
+    /// Adds the game if it ended and both teams are in this play-off, matching names like
+    /// [crate::teams] does. Only pass play-off games, regular-season games between the same
+    /// teams would count too.
+    pub fn add_game(&mut self, game: &Game) -> bool {
+        match MatchResult::from_game(game) {
+            Some(result) => self.add_result(result),
+            None => false,
+        }
+    }
+
+    /// Number of games that were added
+    pub fn add_competition(&mut self, competition: &Competition) -> usize {
+        competition
+            .games
+            .iter()
+            .filter(|game| self.add_game(game))
+            .count()
+    }
+
+    /// Returns whether both teams were found
+    pub fn add_result(&mut self, result: MatchResult) -> bool {
+        let teams: Vec<_> = self.seeds.iter().map(|seed| seed.team.clone()).collect();
+        match (
+            best_match(&result.home_team, &teams),
+            best_match(&result.away_team, &teams),
+        ) {
+            (Some(home_team), Some(away_team)) if home_team != away_team => {
+                self.standings.add_result(MatchResult {
+                    home_team: home_team.clone(),
+                    away_team: away_team.clone(),
+                    ..result
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Records from first to last. Points include the halved regular-season ones.
+    pub fn ranked(&self) -> Vec<&TeamRecord> {
+        let mut ranked = self.standings.ranked();
+        ranked.sort_by_key(|record| {
+            let seed = self.seed(&record.team);
+            (
+                std::cmp::Reverse(record.points),
+                seed.is_some_and(|seed| seed.rounded),
+                seed.map_or(usize::MAX, |seed| seed.regular_rank),
+            )
+        });
+        ranked
+    }
+
+    /// Same shape as a table scraped from BBC
+    pub fn to_league(&self) -> Result<League, OutOfRange> {
+        Ok(League {
+            name: self.name.clone(),
+            entries: self
+                .ranked()
+                .iter()
+                .enumerate()
+                .map(|(idx, record)| record.to_entry(idx + 1))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn seed(&self, team: &str) -> Option<&Seed> {
+        self.seeds.iter().find(|seed| seed.team == team)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::beebs::Entry;
+
+    fn regular_season() -> League {
+        let teams = [
+            ("Union SG", 71),
+            ("Anderlecht", 60),
+            ("Club Brugge", 59),
+            ("Antwerp", 58),
+            ("Genk", 51),
+            ("Cercle Brugge", 50),
+            ("Gent", 49),
+            ("Mechelen", 44),
+            ("Standard", 40),
+            ("Westerlo", 38),
+            ("OH Leuven", 36),
+            ("Sint-Truiden", 35),
+            ("Charleroi", 34),
+            ("Kortrijk", 30),
+            ("Eupen", 25),
+            ("RWDM", 24),
+        ];
+        League {
+            name: String::from("First Division A"),
+            entries: teams
+                .iter()
+                .enumerate()
+                .map(|(idx, (team, points))| Entry {
+                    rank: idx as i8 + 1,
+                    team: team.to_string(),
+                    win: 0,
+                    draw: 0,
+                    lose: 0,
+                    gf: 0,
+                    ga: 0,
+                    points: *points,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn halving_points() {
+        let playoffs = PlayoffFormat::pro_league().start(&regular_season());
+        assert_eq!(playoffs.len(), 3);
+        let sizes: Vec<_> = playoffs.iter().map(|p| p.seeds().len()).collect();
+        assert_eq!(sizes, vec![6, 6, 4]);
+
+        let champions = &playoffs[0];
+        let start: Vec<_> = champions
+            .seeds()
+            .iter()
+            .map(|seed| (seed.team.as_str(), seed.points, seed.rounded))
+            .collect();
+        assert_eq!(
+            start,
+            vec![
+                ("Union SG", 36, true),
+                ("Anderlecht", 30, false),
+                ("Club Brugge", 30, true),
+                ("Antwerp", 29, false),
+                ("Genk", 26, true),
+                ("Cercle Brugge", 25, false),
+            ]
+        );
+        // Level on 30, Brugge's points were rounded up
+        let teams: Vec<_> = champions.ranked().iter().map(|r| r.team.as_str()).collect();
+        assert_eq!(teams[1..3], ["Anderlecht", "Club Brugge"]);
+    }
+
+    #[test]
+    fn continuing_from_results() {
+        let mut playoffs = PlayoffFormat::pro_league().start(&regular_season());
+        let champions = &mut playoffs[0];
+        let result = |home: &str, away: &str, home_score, away_score| MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score,
+            away_score,
+        };
+        assert!(champions.add_result(result("Club Brugge KV", "RSC Anderlecht", 2, 1)));
+        assert!(champions.add_result(result("Cercle", "Union SG", 1, 1)));
+        assert!(!champions.add_result(result("Gent", "Genk", 0, 3)));
+
+        let league = champions.to_league().unwrap();
+        let lines: Vec<_> = league.entries.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "1. Union SG 37pts 0-1-0 1-1",
+                "2. Club Brugge 33pts 1-0-0 2-1",
+                "3. Anderlecht 30pts 0-0-1 1-2",
+                "4. Antwerp 29pts 0-0-0 0-0",
+                "5. Cercle Brugge 26pts 0-1-0 1-1",
+                "6. Genk 26pts 0-0-0 0-0",
+            ]
+        );
+    }
+
+    /// Lines of the first table on a soccerway page, like [Entry] shows them
+    fn soccerway_table(content: &str) -> Vec<String> {
+        let content = &content[content.find("team_rank").unwrap()..];
+        let content = &content[..content.find("</tbody>").unwrap()];
+        let cell = |row: &str, class: &str| {
+            let text = &row[row.find(&format!("class=\"{}", class)).unwrap()..];
+            let text = &text[text.find('>').unwrap() + 1..];
+            text[..text.find('<').unwrap()].trim().to_owned()
+        };
+        content
+            .split("team_rank\"")
+            .skip(1)
+            .map(|row| {
+                let team = &row[row.find("title=\"").unwrap() + 7..];
+                format!(
+                    "{}. {} {}pts {}-{}-{} {}-{}",
+                    cell(row, "rank"),
+                    &team[..team.find('"').unwrap()],
+                    cell(row, "number points"),
+                    cell(row, "number total won"),
+                    cell(row, "number total drawn"),
+                    cell(row, "number total lost"),
+                    cell(row, "number total gf"),
+                    cell(row, "number total ga"),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn champions_play_offs_2021() {
+        let expected = soccerway_table(include_str!("../ranking/soccerway/be2021-playoffs.html"));
+        let entry = |rank: i8, team: &str, points: i8| Entry {
+            rank,
+            team: team.to_owned(),
+            win: 0,
+            draw: 0,
+            lose: 0,
+            gf: 0,
+            ga: 0,
+            points,
+        };
+        let regular_season = League {
+            name: String::from("First Division A"),
+            entries: vec![
+                entry(1, "Club Brugge", 76),
+                entry(2, "Antwerp", 59),
+                entry(3, "Anderlecht", 57),
+                entry(4, "Genk", 55),
+                entry(5, "Oostende", 53),
+            ],
+        };
+        // Only the top 4 went for the title back then
+        let format = PlayoffFormat {
+            groups: vec![PlayoffGroup::new("Champions' play-offs", 1, 4, true)],
+        };
+        let mut champions = format.start(&regular_season).remove(0);
+        let start: Vec<_> = champions
+            .seeds()
+            .iter()
+            .map(|seed| (seed.team.as_str(), seed.points))
+            .collect();
+        assert_eq!(
+            start,
+            vec![
+                ("Club Brugge", 38),
+                ("Antwerp", 30),
+                ("Anderlecht", 29),
+                ("Genk", 28)
+            ]
+        );
+
+        let results = [
+            // First round, the form column of the page only goes back five games
+            ("Club Brugge", "Anderlecht", 2, 2),
+            ("Antwerp", "Genk", 2, 3),
+            ("Genk", "Club Brugge", 3, 0),
+            ("Anderlecht", "Antwerp", 2, 2),
+            ("Antwerp", "Club Brugge", 0, 0),
+            ("Genk", "Anderlecht", 1, 1),
+            ("Club Brugge", "Antwerp", 2, 1),
+            ("Genk", "Antwerp", 4, 0),
+            ("Anderlecht", "Club Brugge", 3, 3),
+            ("Antwerp", "Anderlecht", 1, 0),
+            ("Anderlecht", "Genk", 1, 2),
+            ("Club Brugge", "Genk", 1, 2),
+        ];
+        for (home, away, home_score, away_score) in results {
+            assert!(champions.add_result(MatchResult {
+                home_team: home.to_owned(),
+                away_team: away.to_owned(),
+                home_score,
+                away_score,
+            }));
+        }
+        // Level with Genk on 44, but Genk's points were rounded up
+        let league = champions.to_league().unwrap();
+        let lines: Vec<_> = league.entries.iter().map(|e| e.to_string()).collect();
+        assert_eq!(lines, expected);
+        assert_eq!(lines[0], "1. Club Brugge 44pts 1-3-2 8-11");
+    }
+}
@@ -0,0 +1,252 @@
+//! "Where would we be if it ended now": a scraped table with the live scores applied to it.
+
+use super::{rerank_entries, OutOfRange};
+use crate::generic_structs::*;
+use crate::ranking::beebs::{Entry, League};
+use crate::teams::match_names;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct ProjectedEntry {
+    pub entry: Entry,
+    /// Rank in the table the projection started from
+    pub previous_rank: i8,
+    /// Currently playing
+    pub live: bool,
+}
+
+impl ProjectedEntry {
+    /// Positive when moving up the table
+    pub fn change(&self) -> i8 {
+        self.previous_rank - self.entry.rank
+    }
+}
+
+impl fmt::Display for ProjectedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.entry)?;
+        if self.live {
+            write!(f, "*")?;
+        }
+        match self.change() {
+            0 => Ok(()),
+            change if change > 0 => write!(f, " (+{})", change),
+            change => write!(f, " ({})", change),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectedTable {
+    pub name: String,
+    pub entries: Vec<ProjectedEntry>,
+    /// Games that were meant to be applied, but whose teams could not be found in the table
+    pub unmatched: Vec<Game>,
+}
+
+impl ProjectedTable {
+    /// Applies ongoing games in football to the table. Filter football down to the competition
+    /// of the table first. Finished games are only applied when `include_finished` is set, for
+    /// when the scraped table lags behind.
+    ///
+    /// Teams are matched by name across the two sources (see [crate::teams]). Teams level on
+    /// points are ordered by goal difference, goals scored, and then their order in the table we
+    /// started from, since that one knows the real tiebreakers. Fails for numbers that no longer
+    /// fit in a table.
+    pub fn project(
+        league: &League,
+        football: &Football,
+        include_finished: bool,
+    ) -> Result<Self, OutOfRange> {
+        let mut entries: Vec<_> = league
+            .entries
+            .iter()
+            .map(|entry| ProjectedEntry {
+                entry: entry.clone(),
+                previous_rank: entry.rank,
+                live: false,
+            })
+            .collect();
+
+        let games: Vec<_> = football
+            .iter_games()
+            .map(|(_, _, game)| game)
+            .filter(|game| match game.status {
+                GameStatus::Ongoing(_) => true,
+                GameStatus::Ended => include_finished,
+                _ => false,
+            })
+            .filter(|game| game.home_score.is_some() && game.away_score.is_some())
+            .collect();
+        let game_teams: Vec<_> = games
+            .iter()
+            .flat_map(|game| [game.home_team.clone(), game.away_team.clone()])
+            .collect();
+        let table_teams: Vec<_> = entries.iter().map(|e| e.entry.team.clone()).collect();
+        let pairs = match_names(&game_teams, &table_teams);
+        let find = |team: &str| {
+            pairs
+                .iter()
+                .find(|(game_team, _)| game_team == team)
+                .and_then(|(_, table_team)| {
+                    entries.iter().position(|e| &e.entry.team == table_team)
+                })
+        };
+
+        let mut unmatched = vec![];
+        let mut updates = vec![];
+        for game in games {
+            match (find(&game.home_team), find(&game.away_team)) {
+                (Some(home), Some(away)) => updates.push((
+                    home,
+                    away,
+                    game.home_score.unwrap_or(0),
+                    game.away_score.unwrap_or(0),
+                    game.status != GameStatus::Ended,
+                )),
+                _ => unmatched.push(game.clone()),
+            }
+        }
+        for (home, away, home_score, away_score, live) in updates {
+            apply(&mut entries[home], home_score, away_score, live)?;
+            apply(&mut entries[away], away_score, home_score, live)?;
+        }
+
+        // Ranks are still the ones we started from, so those break the remaining ties
+        let mut table: Vec<_> = entries.iter().map(|e| e.entry.clone()).collect();
+        rerank_entries(&mut table)?;
+        let entries = table
+            .into_iter()
+            .map(|entry| {
+                let before = entries
+                    .iter()
+                    .find(|e| e.entry.team == entry.team)
+                    .expect("Reranked entries come from the projection");
+                ProjectedEntry {
+                    entry,
+                    ..before.clone()
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            name: league.name.clone(),
+            entries,
+            unmatched,
+        })
+    }
+}
+
+fn apply(
+    projected: &mut ProjectedEntry,
+    goals_for: u8,
+    goals_against: u8,
+    live: bool,
+) -> Result<(), OutOfRange> {
+    projected.live |= live;
+    super::add_to_entry(&mut projected.entry, goals_for, goals_against)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::prelude::*;
+
+    fn entry(rank: i8, team: &str, points: i8, gf: i8, ga: i8) -> Entry {
+        Entry {
+            rank,
+            team: team.to_owned(),
+            win: 0,
+            draw: 0,
+            lose: 0,
+            gf,
+            ga,
+            points,
+        }
+    }
+
+    fn game(home: &str, away: &str, score: (u8, u8), status: GameStatus) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: Some(score.0),
+            away_score: Some(score.1),
+            start_time: Utc.with_ymd_and_hms(2024, 3, 2, 19, 0, 0).unwrap(),
+            status,
+        }
+    }
+
+    #[test]
+    fn live_scores_move_teams() {
+        let league = League {
+            name: String::from("Belgian Pro League"),
+            entries: vec![
+                entry(1, "Royale Union Saint-Gilloise", 50, 40, 10),
+                entry(2, "RSC Anderlecht", 48, 40, 20),
+                entry(3, "Club Brugge KV", 46, 40, 20),
+                entry(4, "KRC Genk", 40, 30, 20),
+            ],
+        };
+        let football = Football {
+            countries: vec![Country {
+                name: String::from("Belgium"),
+                competitions: vec![Competition {
+                    name: String::from("First Division A"),
+                    games: vec![
+                        game(
+                            "Club Brugge",
+                            "Union SG",
+                            (2, 0),
+                            GameStatus::Ongoing(String::from("70'")),
+                        ),
+                        game("Anderlecht", "Genk", (1, 1), GameStatus::Ended),
+                        game(
+                            "Westerlo",
+                            "Eupen",
+                            (1, 0),
+                            GameStatus::Ongoing(String::from("5'")),
+                        ),
+                    ],
+                }],
+            }],
+        };
+
+        let projected = ProjectedTable::project(&league, &football, false).unwrap();
+        let teams: Vec<_> = projected.entries.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            teams,
+            vec![
+                "1. Royale Union Saint-Gilloise 50pts 0-0-1 40-12*",
+                "2. Club Brugge KV 49pts 1-0-0 42-20* (+1)",
+                "3. RSC Anderlecht 48pts 0-0-0 40-20 (-1)",
+                "4. KRC Genk 40pts 0-0-0 30-20",
+            ]
+        );
+        assert_eq!(projected.unmatched.len(), 1);
+
+        // Table lagging behind, also apply the finished game
+        let projected = ProjectedTable::project(&league, &football, true).unwrap();
+        assert_eq!(projected.entries[2].entry.team, "RSC Anderlecht");
+        assert_eq!(projected.entries[2].entry.points, 49);
+    }
+
+    #[test]
+    fn too_long_for_a_table() {
+        let league = League {
+            name: String::from("Everyone"),
+            entries: (0..130)
+                .map(|idx| {
+                    entry(
+                        i8::try_from(idx).unwrap_or(i8::MAX),
+                        &idx.to_string(),
+                        0,
+                        0,
+                        0,
+                    )
+                })
+                .collect(),
+        };
+        let projected = ProjectedTable::project(&league, &Football::default(), false);
+        assert_eq!(projected.unwrap_err().value, 128);
+    }
+}
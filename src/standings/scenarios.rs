@@ -0,0 +1,428 @@
+//! "What does Belgium need": every way the last games of a group can go, and where that leaves
+//! everyone.
+
+use super::{match_fixtures, MatchResult, Standings};
+use crate::generic_structs::*;
+use crate::history::Outcome;
+use crate::teams::best_match;
+use std::fmt;
+
+/// More than this and there are too many combinations to be of any use in chat
+const MAX_FIXTURES: usize = 6;
+
+/// Scores gone through over all scenarios, give or take. Scenarios that would need more try
+/// fewer goals.
+const MAX_SCORE_COMBINATIONS: usize = 50_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureOutcome {
+    pub home_team: String,
+    pub away_team: String,
+    /// From the home team's side
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for FixtureOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.outcome {
+            Outcome::Win => write!(f, "{} beat {}", self.home_team, self.away_team),
+            Outcome::Draw => write!(f, "{} draw with {}", self.home_team, self.away_team),
+            Outcome::Loss => write!(f, "{} beat {}", self.away_team, self.home_team),
+        }
+    }
+}
+
+/// One final order of the table, and a set of scores that gets there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankingCase {
+    pub ranking: Vec<String>,
+    /// (home, away) score of every fixture, in the order of the outcomes
+    pub example: Vec<(u8, u8)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scenario {
+    pub outcomes: Vec<FixtureOutcome>,
+    /// Just one if the outcomes alone settle the table, otherwise one for every order the scores
+    /// can lead to
+    pub cases: Vec<RankingCase>,
+    /// Most goals per team tried for the scores. Lower than asked for when that made for too
+    /// many combinations, but at least 1.
+    pub max_goals: u8,
+}
+
+impl Scenario {
+    /// Ranks the team can end up at, 1 being first
+    pub fn positions(&self, team: &str) -> Vec<usize> {
+        let mut positions: Vec<_> = self
+            .cases
+            .iter()
+            .filter_map(|case| case.ranking.iter().position(|t| t == team))
+            .map(|idx| idx + 1)
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    pub fn decided_by_results(&self) -> bool {
+        self.cases.len() == 1
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Scenarios {
+    pub scenarios: Vec<Scenario>,
+    /// Fixtures with a team that is not in the table, left out
+    pub unmatched: Vec<Game>,
+    teams: Vec<String>,
+}
+
+impl Scenarios {
+    /// Goes through every win/draw/loss combination of the fixtures. When that leaves teams level
+    /// on points, also goes through every score up to `max_goals` per team of their games, to see
+    /// what the tiebreakers make of it, with fewer goals if that is too much to go through. None
+    /// if there are more than a handful of fixtures left.
+    ///
+    /// For a scraped group, start from [Standings::from_league] with the group's tiebreakers.
+    /// Head-to-head rules then only know about the fixtures.
+    pub fn compute(standings: &Standings, fixtures: &[Game], max_goals: u8) -> Option<Self> {
+        let teams = standings.teams();
+        let (matched, unmatched) = match_fixtures(&teams, fixtures);
+        if matched.len() > MAX_FIXTURES {
+            return None;
+        }
+        let fixtures: Vec<_> = matched
+            .iter()
+            .map(|(home, away, _)| (teams[*home].clone(), teams[*away].clone()))
+            .collect();
+
+        let mut scenarios = vec![];
+        let total = 3usize.pow(fixtures.len() as u32);
+        let budget = MAX_SCORE_COMBINATIONS / total;
+        for counter in 0..total {
+            let mut c = counter;
+            let outcomes: Vec<_> = fixtures
+                .iter()
+                .map(|(home_team, away_team)| {
+                    let outcome = [Outcome::Win, Outcome::Draw, Outcome::Loss][c % 3];
+                    c /= 3;
+                    FixtureOutcome {
+                        home_team: home_team.clone(),
+                        away_team: away_team.clone(),
+                        outcome,
+                    }
+                })
+                .collect();
+            let (cases, max_goals) = cases(standings, &outcomes, max_goals.max(1), budget);
+            scenarios.push(Scenario {
+                outcomes,
+                cases,
+                max_goals,
+            });
+        }
+
+        Some(Self {
+            scenarios,
+            unmatched,
+            teams,
+        })
+    }
+
+    /// Every rank the team can still end up at
+    pub fn positions(&self, team: &str) -> Vec<usize> {
+        let Some(team) = best_match(team, &self.teams) else {
+            return vec![];
+        };
+        let mut positions: Vec<_> = self
+            .scenarios
+            .iter()
+            .flat_map(|scenario| scenario.positions(team))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// A line per scenario about the team, e.g., "Wales beat Belgium, Czechia beat Estonia: 3rd".
+    /// Team is matched by name (see [crate::teams]).
+    pub fn for_team(&self, team: &str) -> Vec<String> {
+        let Some(team) = best_match(team, &self.teams) else {
+            return vec![];
+        };
+        self.scenarios
+            .iter()
+            .map(|scenario| {
+                let outcomes: Vec<_> = scenario.outcomes.iter().map(|o| o.to_string()).collect();
+                let positions = scenario.positions(team);
+                let mut line = format!(
+                    "{}: {}",
+                    outcomes.join(", "),
+                    positions
+                        .iter()
+                        .map(|p| ordinal(*p))
+                        .collect::<Vec<_>>()
+                        .join(" or ")
+                );
+                if positions.len() > 1 {
+                    let examples: Vec<_> = positions
+                        .iter()
+                        .filter_map(|position| {
+                            let case = scenario
+                                .cases
+                                .iter()
+                                .find(|case| case.ranking.get(position - 1) == Some(team))?;
+                            let scores: Vec<_> = case
+                                .example
+                                .iter()
+                                .map(|(h, a)| format!("{}-{}", h, a))
+                                .collect();
+                            Some(format!("{} with {}", ordinal(*position), scores.join(", ")))
+                        })
+                        .collect();
+                    line.push_str(&format!(
+                        ", depends on the score (e.g., {})",
+                        examples.join("; ")
+                    ));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// The orders the table can end up in for these outcomes, along with the most goals tried to
+/// stay within about `budget` combinations
+fn cases(
+    standings: &Standings,
+    outcomes: &[FixtureOutcome],
+    max_goals: u8,
+    budget: usize,
+) -> (Vec<RankingCase>, u8) {
+    let simplest: Vec<_> = outcomes.iter().map(|o| simplest_score(o.outcome)).collect();
+    let ranked = play(standings, outcomes, &simplest);
+
+    // Only the games of teams level on points can change the order
+    let level: Vec<&String> = ranked
+        .iter()
+        .filter(|(team, points)| {
+            ranked
+                .iter()
+                .any(|(other, other_points)| other != team && other_points == points)
+        })
+        .map(|(team, _)| team)
+        .collect();
+    let options_with = |goals: u8| -> Vec<Vec<(u8, u8)>> {
+        outcomes
+            .iter()
+            .zip(&simplest)
+            .map(|(o, simplest)| {
+                if level.contains(&&o.home_team) || level.contains(&&o.away_team) {
+                    scores(o.outcome, goals)
+                } else {
+                    vec![*simplest]
+                }
+            })
+            .collect()
+    };
+    let mut goals = max_goals;
+    let mut options = options_with(goals);
+    while goals > 1 && options.iter().map(Vec::len).product::<usize>() > budget {
+        goals -= 1;
+        options = options_with(goals);
+    }
+
+    let mut cases: Vec<RankingCase> = vec![];
+    let mut choice = vec![0; options.len()];
+    loop {
+        let scores: Vec<_> = choice.iter().zip(&options).map(|(c, o)| o[*c]).collect();
+        let ranking: Vec<_> = play(standings, outcomes, &scores)
+            .into_iter()
+            .map(|(team, _)| team)
+            .collect();
+        if !cases.iter().any(|case| case.ranking == ranking) {
+            cases.push(RankingCase {
+                ranking,
+                example: scores,
+            });
+        }
+        // Next combination, like counting with a different base for every digit
+        let mut idx = 0;
+        while idx < choice.len() {
+            choice[idx] += 1;
+            if choice[idx] < options[idx].len() {
+                break;
+            }
+            choice[idx] = 0;
+            idx += 1;
+        }
+        if idx == choice.len() {
+            break;
+        }
+    }
+    (cases, goals)
+}
+
+/// Final (team, points), first to last
+fn play(
+    standings: &Standings,
+    outcomes: &[FixtureOutcome],
+    scores: &[(u8, u8)],
+) -> Vec<(String, i32)> {
+    let mut standings = standings.clone();
+    for (outcome, (home_score, away_score)) in outcomes.iter().zip(scores) {
+        standings.add_result(MatchResult {
+            home_team: outcome.home_team.clone(),
+            away_team: outcome.away_team.clone(),
+            home_score: *home_score,
+            away_score: *away_score,
+        });
+    }
+    standings
+        .ranked()
+        .iter()
+        .map(|record| (record.team.clone(), record.points))
+        .collect()
+}
+
+fn simplest_score(outcome: Outcome) -> (u8, u8) {
+    match outcome {
+        Outcome::Win => (1, 0),
+        Outcome::Draw => (0, 0),
+        Outcome::Loss => (0, 1),
+    }
+}
+
+/// Every score with the outcome, up to max goals per team
+fn scores(outcome: Outcome, max_goals: u8) -> Vec<(u8, u8)> {
+    let mut scores = vec![];
+    for home in 0..=max_goals {
+        for away in 0..=max_goals {
+            if Outcome::from_score(home, away) == outcome {
+                scores.push((home, away));
+            }
+        }
+    }
+    scores
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standings::Tiebreakers;
+    use chrono::prelude::*;
+
+    fn fixture(home: &str, away: &str) -> Game {
+        Game {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: None,
+            away_score: None,
+            start_time: Utc.with_ymd_and_hms(2024, 6, 26, 19, 0, 0).unwrap(),
+            status: GameStatus::Upcoming,
+        }
+    }
+
+    fn group() -> Standings {
+        let mut standings = Standings::new("Group A").with_tiebreakers(Tiebreakers::uefa_group());
+        let result = |home: &str, away: &str, home_score, away_score| MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score,
+            away_score,
+        };
+        standings.add_result(result("Belgium", "Wales", 2, 0));
+        standings.add_result(result("Czechia", "Estonia", 1, 0));
+        standings.add_result(result("Belgium", "Czechia", 0, 1));
+        standings.add_result(result("Wales", "Estonia", 1, 0));
+        standings.add_result(result("Estonia", "Belgium", 0, 2));
+        standings.add_result(result("Czechia", "Wales", 0, 0));
+        standings
+    }
+
+    #[test]
+    fn last_matchday() {
+        // Czechia 7, Belgium 6, Wales 4, Estonia 0
+        let fixtures = vec![fixture("Wales", "Belgium"), fixture("Estonia", "Czechia")];
+        let scenarios = Scenarios::compute(&group(), &fixtures, 3).unwrap();
+        assert_eq!(scenarios.scenarios.len(), 9);
+
+        let find = |wales: Outcome, estonia: Outcome| {
+            scenarios
+                .scenarios
+                .iter()
+                .find(|s| s.outcomes[0].outcome == wales && s.outcomes[1].outcome == estonia)
+                .unwrap()
+        };
+        // Both favourites win, nothing to work out
+        let settled = find(Outcome::Loss, Outcome::Loss);
+        assert!(settled.decided_by_results());
+        assert_eq!(
+            settled.cases[0].ranking,
+            vec!["Czechia", "Belgium", "Wales", "Estonia"]
+        );
+        // Wales and Czechia level on 7 with a draw between them, down to the scores
+        let open = find(Outcome::Win, Outcome::Win);
+        assert!(!open.decided_by_results());
+        assert_eq!(open.positions("Wales"), vec![1, 2]);
+        assert_eq!(open.positions("Belgium"), vec![3]);
+
+        assert_eq!(scenarios.positions("Belgium"), vec![1, 2, 3]);
+        assert_eq!(scenarios.positions("Estonia"), vec![4]);
+        let lines = scenarios.for_team("belgium");
+        assert_eq!(lines[0], "Wales beat Belgium, Estonia beat Czechia: 3rd");
+        assert!(lines.contains(&String::from(
+            "Belgium beat Wales, Czechia beat Estonia: 2nd"
+        )));
+        let lines = scenarios.for_team("Wales");
+        assert!(lines[0].starts_with(
+            "Wales beat Belgium, Estonia beat Czechia: 1st or 2nd, depends on the score (e.g., 1st with"
+        ));
+    }
+
+    #[test]
+    fn six_fixtures_stay_within_bounds() {
+        // Everyone level, so every game could matter for the order
+        let mut standings = Standings::new("Group B").with_tiebreakers(Tiebreakers::uefa_group());
+        let teams = ["Spain", "Croatia", "Italy", "Albania"];
+        for team in teams {
+            standings.add_team(team);
+        }
+        let mut fixtures = vec![];
+        for (idx, home) in teams.iter().enumerate() {
+            for away in &teams[idx + 1..] {
+                fixtures.push(fixture(home, away));
+            }
+        }
+        let scenarios = Scenarios::compute(&standings, &fixtures, 5).unwrap();
+        assert_eq!(scenarios.scenarios.len(), 729);
+        let all_draws = scenarios
+            .scenarios
+            .iter()
+            .find(|s| s.outcomes.iter().all(|o| o.outcome == Outcome::Draw))
+            .unwrap();
+        assert!(all_draws.max_goals < 5);
+        assert!(!all_draws.decided_by_results());
+        assert_eq!(scenarios.positions("Albania"), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn ordinals() {
+        let words: Vec<_> = [1, 2, 3, 4, 11, 12, 13, 21, 22].map(ordinal).to_vec();
+        assert_eq!(
+            words,
+            vec!["1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd"]
+        );
+    }
+}
@@ -0,0 +1,487 @@
+//! Deciding the order of teams that are level on points. Every league does this differently.
+
+use super::{MatchResult, TeamRecord};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TiebreakRule {
+    GoalDifference,
+    GoalsScored,
+    AwayGoalsScored,
+    Wins,
+    AwayWins,
+    /// Points in the games between the tied teams
+    HeadToHeadPoints,
+    HeadToHeadGoalDifference,
+    HeadToHeadGoalsScored,
+    HeadToHeadAwayGoalsScored,
+    /// Fewest fair play penalty points (cards), see [super::Standings::set_fair_play]
+    FairPlay,
+    /// Nothing separated them. Officially lots are drawn or a play-off is held, we go
+    /// alphabetical.
+    DrawingOfLots,
+}
+
+impl TiebreakRule {
+    fn is_head_to_head(&self) -> bool {
+        matches!(
+            self,
+            TiebreakRule::HeadToHeadPoints
+                | TiebreakRule::HeadToHeadGoalDifference
+                | TiebreakRule::HeadToHeadGoalsScored
+                | TiebreakRule::HeadToHeadAwayGoalsScored
+        )
+    }
+}
+
+impl fmt::Display for TiebreakRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            TiebreakRule::GoalDifference => "goal difference",
+            TiebreakRule::GoalsScored => "goals scored",
+            TiebreakRule::AwayGoalsScored => "away goals scored",
+            TiebreakRule::Wins => "wins",
+            TiebreakRule::AwayWins => "away wins",
+            TiebreakRule::HeadToHeadPoints => "head-to-head points",
+            TiebreakRule::HeadToHeadGoalDifference => "head-to-head goal difference",
+            TiebreakRule::HeadToHeadGoalsScored => "head-to-head goals scored",
+            TiebreakRule::HeadToHeadAwayGoalsScored => "head-to-head away goals",
+            TiebreakRule::FairPlay => "fair play",
+            TiebreakRule::DrawingOfLots => "drawing of lots",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// The rules in the order they get applied after points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tiebreakers {
+    pub rules: Vec<TiebreakRule>,
+    /// UEFA style: when head-to-head criteria split a group of tied teams, they are applied again
+    /// to only the games between the teams that are still tied.
+    pub reapply_head_to_head: bool,
+}
+
+impl Default for Tiebreakers {
+    fn default() -> Self {
+        Self {
+            rules: vec![TiebreakRule::GoalDifference, TiebreakRule::GoalsScored],
+            reapply_head_to_head: false,
+        }
+    }
+}
+
+impl Tiebreakers {
+    pub fn premier_league() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::HeadToHeadPoints,
+                TiebreakRule::HeadToHeadAwayGoalsScored,
+            ],
+            reapply_head_to_head: false,
+        }
+    }
+
+    pub fn bundesliga() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::HeadToHeadPoints,
+                TiebreakRule::HeadToHeadGoalDifference,
+                TiebreakRule::HeadToHeadAwayGoalsScored,
+                TiebreakRule::AwayGoalsScored,
+            ],
+            reapply_head_to_head: false,
+        }
+    }
+
+    pub fn la_liga() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::HeadToHeadPoints,
+                TiebreakRule::HeadToHeadGoalDifference,
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::FairPlay,
+            ],
+            reapply_head_to_head: false,
+        }
+    }
+
+    pub fn serie_a() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::HeadToHeadPoints,
+                TiebreakRule::HeadToHeadGoalDifference,
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+            ],
+            reapply_head_to_head: false,
+        }
+    }
+
+    /// Belgian Pro League regular season
+    pub fn pro_league() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::Wins,
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::AwayGoalsScored,
+                TiebreakRule::AwayWins,
+            ],
+            reapply_head_to_head: false,
+        }
+    }
+
+    /// Group stages of the Euros and the Nations League
+    pub fn uefa_group() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::HeadToHeadPoints,
+                TiebreakRule::HeadToHeadGoalDifference,
+                TiebreakRule::HeadToHeadGoalsScored,
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::Wins,
+                TiebreakRule::FairPlay,
+            ],
+            reapply_head_to_head: true,
+        }
+    }
+
+    /// The single table of the Champions League and co. since 2024
+    pub fn uefa_league_phase() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::AwayGoalsScored,
+                TiebreakRule::Wins,
+                TiebreakRule::AwayWins,
+            ],
+            reapply_head_to_head: false,
+        }
+    }
+
+    /// World Cup group stage (2026 rules)
+    pub fn fifa_world_cup() -> Self {
+        Self {
+            rules: vec![
+                TiebreakRule::HeadToHeadPoints,
+                TiebreakRule::HeadToHeadGoalDifference,
+                TiebreakRule::HeadToHeadGoalsScored,
+                TiebreakRule::GoalDifference,
+                TiebreakRule::GoalsScored,
+                TiebreakRule::FairPlay,
+            ],
+            reapply_head_to_head: true,
+        }
+    }
+
+    /// Picks a preset based on the country and competition names as livescore has them. Default
+    /// (goal difference, goals scored) if there is no preset.
+    pub fn for_competition(country: &str, competition: &str) -> Self {
+        let country = country.to_lowercase();
+        let competition = competition.to_lowercase();
+        if country.contains("champions league")
+            || country.contains("europa")
+            || country.contains("conference league")
+        {
+            if competition.contains("group") {
+                Self::uefa_group()
+            } else {
+                Self::uefa_league_phase()
+            }
+        } else if country.contains("world cup") {
+            Self::fifa_world_cup()
+        } else if country.contains("euro") || country.contains("nations league") {
+            Self::uefa_group()
+        } else {
+            match (country.as_str(), competition.as_str()) {
+                ("england", "premier league") => Self::premier_league(),
+                ("germany", "bundesliga") => Self::bundesliga(),
+                ("spain", c) if c.starts_with("laliga") => Self::la_liga(),
+                ("italy", "serie a") => Self::serie_a(),
+                ("belgium", "first division a") => Self::pro_league(),
+                _ => Self::default(),
+            }
+        }
+    }
+
+    /// Orders the records, returning for every team the rule that put it above the next team if
+    /// they were level on points.
+    pub(crate) fn rank<'a>(
+        &self,
+        records: &'a [TeamRecord],
+        results: &[MatchResult],
+        fair_play: &HashMap<String, i32>,
+    ) -> Vec<(&'a TeamRecord, Option<TiebreakRule>)> {
+        let mut by_points: Vec<_> = records.iter().collect();
+        by_points.sort_by_key(|record| std::cmp::Reverse(record.points));
+        let context = Context { results, fair_play };
+        let mut ranked = vec![];
+        for group in split_by_key(by_points, |record| i64::from(record.points)) {
+            ranked.extend(self.resolve(&context, group, 0));
+        }
+        ranked
+    }
+
+    fn resolve<'a>(
+        &self,
+        context: &Context,
+        group: Vec<&'a TeamRecord>,
+        first_rule: usize,
+    ) -> Vec<(&'a TeamRecord, Option<TiebreakRule>)> {
+        if group.len() == 1 {
+            return vec![(group[0], None)];
+        }
+        for (idx, rule) in self.rules.iter().enumerate().skip(first_rule) {
+            let keys = context.keys(*rule, &group);
+            let mut keyed: Vec<_> = group.iter().copied().zip(keys).collect();
+            keyed.sort_by(|(_, a), (_, b)| b.cmp(a));
+            let subgroups = split_by_key(keyed, |(_, key)| *key);
+            if subgroups.len() == 1 {
+                continue;
+            }
+            let mut result: Vec<(&TeamRecord, Option<TiebreakRule>)> = vec![];
+            for subgroup in subgroups {
+                if let Some(last) = result.last_mut() {
+                    last.1 = Some(*rule);
+                }
+                let subgroup = subgroup.into_iter().map(|(record, _)| record).collect();
+                let next_rule = if self.reapply_head_to_head && rule.is_head_to_head() {
+                    0
+                } else {
+                    idx + 1
+                };
+                result.extend(self.resolve(context, subgroup, next_rule));
+            }
+            return result;
+        }
+        let mut group = group;
+        group.sort_by(|a, b| a.team.cmp(&b.team));
+        let last = group.len() - 1;
+        group
+            .into_iter()
+            .enumerate()
+            .map(|(idx, record)| {
+                let rule = (idx < last).then_some(TiebreakRule::DrawingOfLots);
+                (record, rule)
+            })
+            .collect()
+    }
+}
+
+struct Context<'a> {
+    results: &'a [MatchResult],
+    fair_play: &'a HashMap<String, i32>,
+}
+
+impl Context<'_> {
+    /// Value per team for the rule, higher is better
+    fn keys(&self, rule: TiebreakRule, group: &[&TeamRecord]) -> Vec<i64> {
+        let teams: Vec<_> = group.iter().map(|record| record.team.as_str()).collect();
+        match rule {
+            TiebreakRule::GoalDifference => group.iter().map(|r| r.gd().into()).collect(),
+            TiebreakRule::GoalsScored => group.iter().map(|r| r.gf.into()).collect(),
+            TiebreakRule::Wins => group.iter().map(|r| r.win.into()).collect(),
+            TiebreakRule::AwayGoalsScored => {
+                let away = self.away_records(self.results.iter());
+                teams
+                    .iter()
+                    .map(|team| away_key(&away, team, |r| r.gf.into()))
+                    .collect()
+            }
+            TiebreakRule::AwayWins => {
+                let away = self.away_records(self.results.iter());
+                teams
+                    .iter()
+                    .map(|team| away_key(&away, team, |r| r.win.into()))
+                    .collect()
+            }
+            TiebreakRule::HeadToHeadPoints
+            | TiebreakRule::HeadToHeadGoalDifference
+            | TiebreakRule::HeadToHeadGoalsScored => {
+                let mut mini = super::Standings::new("");
+                for team in &teams {
+                    mini.add_team(team);
+                }
+                for result in self.between(&teams) {
+                    mini.add_result(result.clone());
+                }
+                teams
+                    .iter()
+                    .map(|team| {
+                        let record = mini.records.iter().find(|r| r.team == *team).unwrap();
+                        match rule {
+                            TiebreakRule::HeadToHeadPoints => record.points.into(),
+                            TiebreakRule::HeadToHeadGoalDifference => record.gd().into(),
+                            _ => record.gf.into(),
+                        }
+                    })
+                    .collect()
+            }
+            TiebreakRule::HeadToHeadAwayGoalsScored => {
+                let away = self.away_records(self.between(&teams));
+                teams
+                    .iter()
+                    .map(|team| away_key(&away, team, |r| r.gf.into()))
+                    .collect()
+            }
+            TiebreakRule::FairPlay => teams
+                .iter()
+                .map(|team| -i64::from(*self.fair_play.get(*team).unwrap_or(&0)))
+                .collect(),
+            TiebreakRule::DrawingOfLots => vec![0; group.len()],
+        }
+    }
+
+    /// Results of games where both teams are in the group
+    fn between<'c>(&'c self, teams: &'c [&str]) -> impl Iterator<Item = &'c MatchResult> + 'c {
+        self.results.iter().filter(move |result| {
+            teams.contains(&result.home_team.as_str()) && teams.contains(&result.away_team.as_str())
+        })
+    }
+
+    /// Records counting only away games
+    fn away_records<'c>(
+        &self,
+        results: impl Iterator<Item = &'c MatchResult>,
+    ) -> HashMap<&'c str, TeamRecord> {
+        let mut away: HashMap<&str, TeamRecord> = HashMap::new();
+        for result in results {
+            away.entry(&result.away_team)
+                .or_insert_with(|| TeamRecord::new(&result.away_team))
+                .add(result.away_score, result.home_score);
+        }
+        away
+    }
+}
+
+fn away_key(away: &HashMap<&str, TeamRecord>, team: &str, key: impl Fn(&TeamRecord) -> i64) -> i64 {
+    away.get(team).map(key).unwrap_or(0)
+}
+
+/// Splits a sorted list into runs with the same key
+fn split_by_key<T>(sorted: Vec<T>, key: impl Fn(&T) -> i64) -> Vec<Vec<T>> {
+    let mut groups: Vec<Vec<T>> = vec![];
+    let mut last_key = None;
+    for item in sorted {
+        let item_key = key(&item);
+        match groups.last_mut() {
+            Some(group) if last_key == Some(item_key) => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+        last_key = Some(item_key);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Standings;
+    use super::*;
+
+    fn add(standings: &mut Standings, home: &str, away: &str, score: (u8, u8)) {
+        standings.add_result(MatchResult {
+            home_team: home.to_owned(),
+            away_team: away.to_owned(),
+            home_score: score.0,
+            away_score: score.1,
+        });
+    }
+
+    /// A beat B, but B has the better goal difference. Both on 6 points.
+    fn standings() -> Standings {
+        let mut standings = Standings::new("Group");
+        add(&mut standings, "A", "B", (1, 0));
+        add(&mut standings, "B", "C", (5, 0));
+        add(&mut standings, "A", "D", (0, 1));
+        add(&mut standings, "C", "A", (0, 1));
+        add(&mut standings, "D", "B", (0, 2));
+        add(&mut standings, "C", "D", (2, 2));
+        standings
+    }
+
+    #[test]
+    fn goal_difference_first() {
+        let standings = standings().with_tiebreakers(Tiebreakers::premier_league());
+        let ranked = standings.ranked_with_tiebreaks();
+        assert_eq!(ranked[0].0.team, "B");
+        assert_eq!(ranked[0].1, Some(TiebreakRule::GoalDifference));
+        assert_eq!(ranked[1].0.team, "A");
+        assert_eq!(ranked[1].1, None);
+    }
+
+    #[test]
+    fn head_to_head_first() {
+        let standings = standings().with_tiebreakers(Tiebreakers::la_liga());
+        let ranked = standings.ranked_with_tiebreaks();
+        assert_eq!(ranked[0].0.team, "A");
+        assert_eq!(ranked[0].1, Some(TiebreakRule::HeadToHeadPoints));
+        assert_eq!(
+            standings.tiebreak_notes(),
+            vec!["A above B on head-to-head points"]
+        );
+    }
+
+    #[test]
+    fn head_to_head_after_goals() {
+        // A and B level on points and goals, both won their home game against each other. B
+        // scored more away goals, which only comes after the head-to-head goal difference.
+        let mut standings =
+            Standings::new("Bundesliga").with_tiebreakers(Tiebreakers::bundesliga());
+        add(&mut standings, "A", "B", (3, 0));
+        add(&mut standings, "B", "A", (1, 0));
+        add(&mut standings, "C", "A", (5, 0));
+        add(&mut standings, "D", "B", (3, 2));
+        let ranked = standings.ranked_with_tiebreaks();
+        let teams: Vec<_> = ranked
+            .iter()
+            .map(|(record, _)| record.team.as_str())
+            .collect();
+        assert_eq!(teams[2..], ["A", "B"]);
+        assert_eq!(ranked[2].1, Some(TiebreakRule::HeadToHeadGoalDifference));
+    }
+
+    #[test]
+    fn reapply_among_remaining() {
+        // Three teams on 6 points, all beat each other 1-0 in a circle. D lost everything.
+        let mut standings = Standings::new("Group").with_tiebreakers(Tiebreakers::uefa_group());
+        add(&mut standings, "A", "B", (1, 0));
+        add(&mut standings, "B", "C", (1, 0));
+        add(&mut standings, "C", "A", (1, 0));
+        add(&mut standings, "A", "D", (3, 0));
+        add(&mut standings, "B", "D", (2, 0));
+        add(&mut standings, "C", "D", (1, 0));
+        // Head-to-head between A, B, and C is all equal, so overall goal difference decides.
+        let teams: Vec<_> = standings
+            .ranked_with_tiebreaks()
+            .iter()
+            .map(|(record, rule)| (record.team.clone(), *rule))
+            .collect();
+        assert_eq!(
+            teams,
+            vec![
+                (String::from("A"), Some(TiebreakRule::GoalDifference)),
+                (String::from("B"), Some(TiebreakRule::GoalDifference)),
+                (String::from("C"), None),
+                (String::from("D"), None),
+            ]
+        );
+
+        // Fair play when everything else is equal
+        let mut standings = Standings::new("Group").with_tiebreakers(Tiebreakers::uefa_group());
+        add(&mut standings, "A", "B", (1, 1));
+        standings.set_fair_play("A", 3);
+        standings.set_fair_play("B", 1);
+        let ranked = standings.ranked_with_tiebreaks();
+        assert_eq!(ranked[0].0.team, "B");
+        assert_eq!(ranked[0].1, Some(TiebreakRule::FairPlay));
+    }
+}
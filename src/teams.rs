@@ -0,0 +1,162 @@
+//! Matching team names between sources. Livescore says "Club Brugge", BBC says "Club Brugge KV",
+//! someone on IRC says "brugge".
+
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Bits of club names that say nothing about which club it is
+const NOISE: &[&str] = &[
+    "fc", "afc", "cf", "sc", "kv", "krc", "rsc", "kaa", "kas", "kvc", "rfc", "sv", "fk", "ac",
+    "as", "cd", "ud", "sd", "rc", "royal", "k",
+];
+
+/// Common short forms, expanded before comparing
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("man", "manchester"),
+    ("utd", "united"),
+    ("st", "saint"),
+    ("sg", "saint gilloise"),
+    ("spurs", "tottenham hotspur"),
+    ("wolves", "wolverhampton wanderers"),
+    ("bayern", "bayern munich"),
+    ("psg", "paris saint germain"),
+];
+
+/// Lowercase words of the name without accents, punctuation, or noise like "FC"
+pub fn normalize(name: &str) -> Vec<String> {
+    let folded: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    let mut words = vec![];
+    for word in folded.split_whitespace() {
+        if NOISE.contains(&word) {
+            continue;
+        }
+        match ABBREVIATIONS.iter().find(|(short, _)| *short == word) {
+            Some((_, long)) => words.extend(long.split(' ').map(String::from)),
+            None => words.push(word.to_owned()),
+        }
+    }
+    words
+}
+
+/// How well two names match, higher is better. None if they do not seem to be the same team.
+pub fn similarity(a: &str, b: &str) -> Option<i64> {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    if a == b {
+        return Some(i64::MAX);
+    }
+    let (short, long) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    if short.iter().all(|word| long.contains(word)) {
+        // "brugge" is in "club brugge", but "club" is also in "cercle brugge club". Fewer left
+        // over words is a better match.
+        return Some(i64::MAX / 2 - (long.len() - short.len()) as i64);
+    }
+    let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+    let score = matcher.fuzzy_match(&long.join(" "), &short.join(" "))?;
+    // Skim gives a bit over 20 per matching character, so require most of the name to match
+    let needed = 16 * short.join(" ").len() as i64;
+    (score >= needed).then_some(score)
+}
+
+/// The candidate that best matches name, if any matches at all
+pub fn best_match<'a>(name: &str, candidates: &'a [String]) -> Option<&'a String> {
+    candidates
+        .iter()
+        .filter_map(|candidate| similarity(name, candidate).map(|score| (score, candidate)))
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Pairs up names from two sources, best matches first, every name used at most once. Returns
+/// (from, to) pairs.
+pub fn match_names(from: &[String], to: &[String]) -> Vec<(String, String)> {
+    let mut scored = vec![];
+    for a in from {
+        for b in to {
+            if let Some(score) = similarity(a, b) {
+                scored.push((score, a, b));
+            }
+        }
+    }
+    scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+    let mut result: Vec<(String, String)> = vec![];
+    for (_, a, b) in scored {
+        if result.iter().any(|(x, y)| x == a || y == b) {
+            continue;
+        }
+        result.push((a.clone(), b.clone()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizing() {
+        assert_eq!(normalize("Club Brugge KV"), vec!["club", "brugge"]);
+        assert_eq!(normalize("Standard Liège"), vec!["standard", "liege"]);
+        assert_eq!(normalize("Man Utd"), vec!["manchester", "united"]);
+    }
+
+    #[test]
+    fn matching_across_sources() {
+        let bbc: Vec<_> = [
+            "Club Brugge KV",
+            "Cercle Brugge",
+            "Royale Union Saint-Gilloise",
+            "Standard Liège",
+            "KRC Genk",
+            "KAA Gent",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let livescore: Vec<_> = [
+            "Gent",
+            "Genk",
+            "Union SG",
+            "Club Brugge",
+            "Cercle Brugge",
+            "Standard Liege",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let pairs = match_names(&livescore, &bbc);
+        let lookup = |name: &str| {
+            pairs
+                .iter()
+                .find(|(a, _)| a == name)
+                .map(|(_, b)| b.as_str())
+        };
+        assert_eq!(lookup("Club Brugge"), Some("Club Brugge KV"));
+        assert_eq!(lookup("Cercle Brugge"), Some("Cercle Brugge"));
+        assert_eq!(lookup("Union SG"), Some("Royale Union Saint-Gilloise"));
+        assert_eq!(lookup("Genk"), Some("KRC Genk"));
+        assert_eq!(lookup("Gent"), Some("KAA Gent"));
+        assert_eq!(lookup("Standard Liege"), Some("Standard Liège"));
+        assert_eq!(best_match("arsenal", &bbc), None);
+    }
+}